@@ -0,0 +1,281 @@
+use crate::bitboard::Bitboard;
+use crate::board::{NUM_COLS, NUM_ROWS};
+use crate::pieces::Id;
+use std::sync::OnceLock;
+
+const NUM_SQUARES: usize = NUM_COLS * NUM_ROWS;
+
+// seeds for each rank that find magics quickly, so the same ones are found on every run
+const SEEDS: [u64; NUM_ROWS] = [728, 10316, 55013, 32803, 12281, 15100, 16645, 255];
+
+// directions each piece can move in
+const DIAGONALS: [(i8, i8); 4] = [(1, 1), (1, -1), (-1, 1), (-1, -1)];
+const STRAIGHTS: [(i8, i8); 4] = [(1, 0), (-1, 0), (0, 1), (0, -1)];
+const KNIGHT_JUMPS: [(i8, i8); 8] = [
+    (1, 2),
+    (2, 1),
+    (2, -1),
+    (1, -2),
+    (-1, -2),
+    (-2, -1),
+    (-2, 1),
+    (-1, 2),
+];
+const KING_STEPS: [(i8, i8); 8] = [
+    (1, 1),
+    (1, -1),
+    (-1, 1),
+    (-1, -1),
+    (1, 0),
+    (-1, 0),
+    (0, 1),
+    (0, -1),
+];
+
+/// Hashes the pieces blocking a slider into its slice of the shared attack table
+/// * `mask` - squares whose occupancy changes the attacks, leaving out the board edges
+/// * `magic` - multiplier that sends every occupancy of `mask` to a slot with the right attacks
+/// * `shift` - leaves as many bits as there are squares in `mask`
+/// * `offset` - start of this square's slice of the table
+struct Magic {
+    mask: Bitboard,
+    magic: u64,
+    shift: u32,
+    offset: usize,
+}
+
+impl Magic {
+    fn index(&self, occupied: Bitboard) -> usize {
+        let hash = (occupied & self.mask).wrapping_mul(self.magic) >> self.shift;
+        self.offset + hash as usize
+    }
+}
+
+/// Attacks for every square, generated once and shared
+/// * `pawn` - indexed by colour, white first
+/// * `sliding` - bishop and rook attacks for every relevant occupancy, indexed through [Magic]
+struct Tables {
+    knight: [Bitboard; NUM_SQUARES],
+    king: [Bitboard; NUM_SQUARES],
+    pawn: [[Bitboard; NUM_SQUARES]; 2],
+    bishop: Vec<Magic>,
+    rook: Vec<Magic>,
+    sliding: Vec<Bitboard>,
+}
+
+static TABLES: OnceLock<Tables> = OnceLock::new();
+
+fn tables() -> &'static Tables {
+    TABLES.get_or_init(Tables::new)
+}
+
+/// Builds the tables ahead of time, so the first search isn't slowed down
+pub fn init() {
+    tables();
+}
+
+pub fn knight(square: usize) -> Bitboard {
+    tables().knight[square]
+}
+
+pub fn king(square: usize) -> Bitboard {
+    tables().king[square]
+}
+
+/// Squares a pawn attacks diagonally
+pub fn pawn(white: bool, square: usize) -> Bitboard {
+    tables().pawn[if white { 0 } else { 1 }][square]
+}
+
+pub fn bishop(square: usize, occupied: Bitboard) -> Bitboard {
+    let tables = tables();
+    tables.sliding[tables.bishop[square].index(occupied)]
+}
+
+pub fn rook(square: usize, occupied: Bitboard) -> Bitboard {
+    let tables = tables();
+    tables.sliding[tables.rook[square].index(occupied)]
+}
+
+pub fn queen(square: usize, occupied: Bitboard) -> Bitboard {
+    bishop(square, occupied) | rook(square, occupied)
+}
+
+/// Squares a piece attacks, including those occupied by either side
+/// * pawns only attack diagonally
+pub fn piece(id: &Id, white: bool, square: usize, occupied: Bitboard) -> Bitboard {
+    match id {
+        Id::Pawn => pawn(white, square),
+        Id::Knight => knight(square),
+        Id::Bishop => bishop(square, occupied),
+        Id::Rook => rook(square, occupied),
+        Id::Queen => queen(square, occupied),
+        Id::King => king(square),
+    }
+}
+
+impl Tables {
+    fn new() -> Tables {
+        let mut tables = Tables {
+            knight: [0; NUM_SQUARES],
+            king: [0; NUM_SQUARES],
+            pawn: [[0; NUM_SQUARES]; 2],
+            bishop: Vec::with_capacity(NUM_SQUARES),
+            rook: Vec::with_capacity(NUM_SQUARES),
+            sliding: Vec::new(),
+        };
+
+        for square in 0..NUM_SQUARES {
+            tables.knight[square] = steps(square, &KNIGHT_JUMPS);
+            tables.king[square] = steps(square, &KING_STEPS);
+            tables.pawn[0][square] = steps(square, &[(-1, 1), (1, 1)]);
+            tables.pawn[1][square] = steps(square, &[(-1, -1), (1, -1)]);
+        }
+
+        for square in 0..NUM_SQUARES {
+            let magic = find_magic(square, &DIAGONALS, &mut tables.sliding);
+            tables.bishop.push(magic);
+        }
+        for square in 0..NUM_SQUARES {
+            let magic = find_magic(square, &STRAIGHTS, &mut tables.sliding);
+            tables.rook.push(magic);
+        }
+
+        tables
+    }
+}
+
+/// Small and fast generator for candidate magics
+pub(crate) struct Xorshift(pub(crate) u64);
+
+impl Xorshift {
+    pub(crate) fn next(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(2685821657736338717)
+    }
+
+    /// Random number with about an eighth of its bits set, which makes better magics
+    fn sparse(&mut self) -> u64 {
+        self.next() & self.next() & self.next()
+    }
+}
+
+/// Moves from a square by (dx, dy), if it stays on the board
+fn offset(square: usize, dx: i8, dy: i8) -> Option<usize> {
+    let x = (square % NUM_COLS) as i8 + dx;
+    let y = (square / NUM_COLS) as i8 + dy;
+    if x < 0 || y < 0 || x >= NUM_COLS as i8 || y >= NUM_ROWS as i8 {
+        return None;
+    }
+
+    Some(y as usize * NUM_COLS + x as usize)
+}
+
+/// Squares one step away in each direction
+fn steps(square: usize, directions: &[(i8, i8)]) -> Bitboard {
+    directions
+        .iter()
+        .filter_map(|(dx, dy)| offset(square, *dx, *dy))
+        .fold(0, |bitboard, square| bitboard | 1 << square)
+}
+
+/// Walks along each direction up to and including the first occupied square
+/// * only used to fill the tables
+fn slide(square: usize, directions: &[(i8, i8)], occupied: Bitboard) -> Bitboard {
+    let mut bitboard = 0;
+    for (dx, dy) in directions {
+        let mut current = square;
+        while let Some(next) = offset(current, *dx, *dy) {
+            bitboard |= 1 << next;
+            if occupied & 1 << next != 0 {
+                break;
+            }
+            current = next;
+        }
+    }
+
+    bitboard
+}
+
+/// Squares along each direction that could block, which leaves out the last one before the edge
+fn mask(square: usize, directions: &[(i8, i8)]) -> Bitboard {
+    let mut bitboard = 0;
+    for (dx, dy) in directions {
+        let mut current = square;
+        while let Some(next) = offset(current, *dx, *dy) {
+            if offset(next, *dx, *dy).is_none() {
+                break;
+            }
+            bitboard |= 1 << next;
+            current = next;
+        }
+    }
+
+    bitboard
+}
+
+/// Tries random sparse multipliers until one hashes every occupancy without a bad collision,
+/// then adds the attacks to the shared table
+fn find_magic(square: usize, directions: &[(i8, i8)], sliding: &mut Vec<Bitboard>) -> Magic {
+    let mut rng = Xorshift(SEEDS[square / NUM_COLS]);
+    let mask = mask(square, directions);
+    let bits = mask.count_ones();
+    let size = 1 << bits;
+
+    // every subset of the mask, found with the carry-rippler trick
+    let mut occupancies: Vec<Bitboard> = Vec::with_capacity(size);
+    let mut subset: Bitboard = 0;
+    loop {
+        occupancies.push(subset);
+        subset = subset.wrapping_sub(mask) & mask;
+        if subset == 0 {
+            break;
+        }
+    }
+    let attacks: Vec<Bitboard> = occupancies
+        .iter()
+        .map(|occupied| slide(square, directions, *occupied))
+        .collect();
+
+    // slots are only trusted if they were filled while trying the current magic
+    let mut table: Vec<Bitboard> = vec![0; size];
+    let mut filled: Vec<usize> = vec![0; size];
+    let mut attempt = 0;
+    loop {
+        let magic = rng.sparse();
+
+        // magics that spread the mask into too few high bits rarely work
+        if (mask.wrapping_mul(magic) >> 56).count_ones() < 6 {
+            continue;
+        }
+
+        attempt += 1;
+        let candidate = Magic {
+            mask,
+            magic,
+            shift: 64 - bits,
+            offset: 0,
+        };
+        let fits = occupancies.iter().zip(&attacks).all(|(occupied, attacks)| {
+            let index = candidate.index(*occupied);
+            if filled[index] == attempt {
+                return table[index] == *attacks;
+            }
+
+            filled[index] = attempt;
+            table[index] = *attacks;
+            true
+        });
+
+        if fits {
+            let offset = sliding.len();
+            sliding.extend(&table);
+            return Magic {
+                offset,
+                ..candidate
+            };
+        }
+    }
+}
@@ -0,0 +1,38 @@
+use chess::attacks;
+use chess::uci::Uci;
+use chess::zobrist;
+use std::error::Error;
+use std::fs;
+use std::io::{self, BufRead};
+
+fn main() -> Result<(), Box<dyn Error>> {
+    // `--keys <file>` swaps in other keys for books made with them, before any board
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match args.as_slice() {
+        [] => (),
+        [flag, path] if flag == "--keys" => {
+            let keys = zobrist::parse_keys(&fs::read_to_string(path)?);
+            if !keys.is_some_and(|keys| zobrist::use_keys(&keys)) {
+                return Err(format!("{} should have {} keys", path, zobrist::NUM_KEYS).into());
+            }
+        }
+        _ => return Err("usage: uci [--keys <file>]".into()),
+    }
+
+    // build the attack tables before the GUI starts the clock
+    attacks::init();
+
+    let stdin = io::stdin();
+    let mut uci = Uci::new(io::stdout());
+
+    // GUIs send one command per line
+    for line in stdin.lock().lines() {
+        if !uci.handle(&line?)? {
+            return Ok(());
+        }
+    }
+
+    // let any search finish if the GUI closes the pipe without quitting
+    uci.wait();
+    Ok(())
+}
@@ -0,0 +1,199 @@
+use crate::attacks;
+use crate::board::{KINGSIDE_CASTLE, NUM_COLS, NUM_ROWS, QUEENSIDE_CASTLE};
+use crate::pieces::moves::MoveType;
+use crate::pieces::Id;
+
+/// Set of squares, one bit each, with A1 as bit 0 and H8 as bit 63
+pub type Bitboard = u64;
+
+/// Piece types in the order of [Id::index]
+const IDS: [Id; 6] = [
+    Id::Pawn,
+    Id::Knight,
+    Id::Bishop,
+    Id::Rook,
+    Id::Queen,
+    Id::King,
+];
+
+/// Index of the square at (x, y)
+pub fn square(x: usize, y: usize) -> usize {
+    y * NUM_COLS + x
+}
+
+/// Bitboard with only the square at (x, y) set
+pub fn bit(x: usize, y: usize) -> Bitboard {
+    1 << square(x, y)
+}
+
+/// Visits each set square, from A1 to H8
+pub fn squares(mut bitboard: Bitboard) -> impl Iterator<Item = usize> {
+    std::iter::from_fn(move || {
+        if bitboard == 0 {
+            return None;
+        }
+
+        let square = bitboard.trailing_zeros() as usize;
+        bitboard &= bitboard - 1;
+        Some(square)
+    })
+}
+
+/// Empty squares a pawn can move straight ahead to
+/// * pawns on their starting rank can move 2 squares if both are empty
+pub fn pawn_pushes(white: bool, square: usize, occupied: Bitboard) -> Bitboard {
+    let forward = |bitboard: Bitboard| match white {
+        true => bitboard << NUM_COLS,
+        false => bitboard >> NUM_COLS,
+    };
+
+    let one = forward(1 << square) & !occupied;
+    let starting_rank = if white { 1 } else { NUM_ROWS - 2 };
+    if square / NUM_COLS != starting_rank {
+        return one;
+    }
+
+    one | (forward(one) & !occupied)
+}
+
+/// One bitboard for each piece type and colour
+/// * `pieces` - indexed by colour, white first, then by [Id::index]
+#[derive(Clone, Copy, PartialEq)]
+pub struct Bitboards {
+    pub pieces: [[Bitboard; 6]; 2],
+}
+
+impl Default for Bitboards {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Bitboards {
+    pub fn new() -> Bitboards {
+        Bitboards {
+            pieces: [[0; 6]; 2],
+        }
+    }
+
+    fn colour_index(white: bool) -> usize {
+        if white {
+            0
+        } else {
+            1
+        }
+    }
+
+    /// Squares with pieces of one type and colour
+    pub fn get(&self, id: &Id, white: bool) -> Bitboard {
+        self.pieces[Self::colour_index(white)][id.index()]
+    }
+
+    /// Squares with pieces of one colour
+    pub fn colour(&self, white: bool) -> Bitboard {
+        self.pieces[Self::colour_index(white)]
+            .iter()
+            .fold(0, |all, bitboard| all | bitboard)
+    }
+
+    /// Squares with any piece
+    pub fn occupied(&self) -> Bitboard {
+        self.colour(true) | self.colour(false)
+    }
+
+    /// Type and colour of the piece on a square
+    pub fn piece_at(&self, square: usize) -> Option<(Id, bool)> {
+        for white in [true, false] {
+            for id in &IDS {
+                if self.get(id, white) & 1 << square != 0 {
+                    return Some((id.clone(), white));
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Puts a piece on a square, replacing anything already there
+    pub fn set(&mut self, square: usize, id: &Id, white: bool) {
+        self.clear(square);
+        self.pieces[Self::colour_index(white)][id.index()] |= 1 << square;
+    }
+
+    /// Removes whatever is on a square
+    pub fn clear(&mut self, square: usize) {
+        for colour in self.pieces.iter_mut() {
+            for bitboard in colour.iter_mut() {
+                *bitboard &= !(1 << square);
+            }
+        }
+    }
+
+    /// Pieces of one colour attacking a square
+    pub fn attackers(&self, square: usize, white: bool) -> Bitboard {
+        let occupied = self.occupied();
+
+        // a square is attacked by a piece if that piece could be attacked from the square
+        IDS.iter().fold(0, |bitboard, id| {
+            bitboard | (attacks::piece(id, !white, square, occupied) & self.get(id, white))
+        })
+    }
+
+    /// Checks if any piece of one colour attacks a square
+    pub fn attacked(&self, square: usize, white: bool) -> bool {
+        self.attackers(square, white) != 0
+    }
+
+    /// Checks if the king is in check
+    /// * returns `false` if there is no king, for custom boards
+    pub fn in_check(&self, white: bool) -> bool {
+        let king = self.get(&Id::King, white);
+        if king == 0 {
+            return false;
+        }
+
+        self.attacked(king.trailing_zeros() as usize, !white)
+    }
+
+    /// Makes a move on the bitboards alone, without validating it
+    pub fn apply(&mut self, move_type: &MoveType, white: bool) {
+        match move_type {
+            MoveType::Normal {
+                piece,
+                target,
+                promotion,
+            } => {
+                let id = match promotion.map(Id::from_char) {
+                    Some(Ok(id)) => id,
+                    _ => piece.id.clone(),
+                };
+                self.clear(square(piece.position.x, piece.position.y));
+                self.set(square(target.x, target.y), &id, white);
+            }
+            MoveType::Castle {
+                king_x,
+                rook_x,
+                kingside,
+            } => {
+                let rank = if white { 0 } else { NUM_ROWS - 1 };
+                let files = match kingside {
+                    true => KINGSIDE_CASTLE,
+                    false => QUEENSIDE_CASTLE,
+                };
+                self.clear(square(*king_x, rank));
+                self.clear(square(*rook_x, rank));
+                self.set(square(files[0], rank), &Id::King, white);
+                self.set(square(files[1], rank), &Id::Rook, white);
+            }
+            MoveType::EnPassant {
+                from,
+                target,
+                capture,
+            } => {
+                self.clear(square(from.x, from.y));
+                self.clear(square(capture.x, capture.y));
+                self.set(square(target.x, target.y), &Id::Pawn, white);
+            }
+        }
+    }
+}
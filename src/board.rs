@@ -1,15 +1,23 @@
+use crate::attacks;
+use crate::bitboard::{self, Bitboards};
+use crate::clock::{self, Clock};
 use crate::coordinate::Coordinate;
-use crate::pieces::moves::MoveType;
+use crate::game::{GameResult, Termination};
+use crate::pieces::moves::{MoveRecord, MoveType};
 use crate::pieces::{Id, MoveChecker, Piece};
-use crate::Error;
+use crate::start;
+use crate::state::State;
+use crate::zobrist;
+use crate::{Error, MoveError};
 use rand::Rng;
-use std::{thread, time};
 
 pub const NUM_COLS: usize = 8;
 pub const NUM_ROWS: usize = 8;
 pub const KINGSIDE_CASTLE: [usize; 2] = [6, 5];
 pub const QUEENSIDE_CASTLE: [usize; 2] = [2, 3];
 pub const EN_PASSANT: [usize; 2] = [4, 3];
+pub const PROMOTIONS: [char; 4] = ['♕', '♖', '♗', '♘'];
+pub const CHESS960_POSITIONS: u16 = 960;
 
 // \u{001b}[38;5;<n>m -> foreground colour for some n
 // \u{001b}[48;5;<n>m -> background colour for some value of n
@@ -18,30 +26,57 @@ const WHITE_COLOUR: &str = "\u{001b}[38;5;255m";
 const BLACK_COLOUR: &str = "\u{001b}[38;5;232m";
 const WARNING_COLOUR: &str = "\u{001b}[31m";
 
-const LOADING_ICON: &str = "* ";
-const INTERVAL: time::Duration = time::Duration::from_millis(500);
-
 // inaccessible coordinate used to test for ambiguity
 const AMBIGUOUS: usize = NUM_COLS + 10;
 
-/// Stores the pieces as in a 2D array
-/// * `grid` - 2D array of options of [Piece]
+/// Stores the pieces as in a 2D array, mirrored by bitboards for fast move generation
+/// * `grid` - 2D array of options of [Piece], changed through [Board::place_piece] and [Board::remove_piece]
+/// * `bitboards` - [Bitboards] with the same pieces as `grid`
 /// * `message` - feedback printed on top of move prompt
+/// * `history` - moves in standard algebraic notation, in the order they were made
+/// * `state` - side to move, castling rights, en passant and move counters, see [State]
+/// * `positions` - hashes of every position reached, for threefold repetition
+/// * `records` - moves that can be undone, oldest first
+/// * `undone` - moves that can be redone, most recently undone last
+/// * `hash` - Zobrist key of the position, updated with each move, see [zobrist]
+/// * `clock` - time left for each player, if the game is timed
 #[derive(Clone)]
 pub struct Board {
     pub grid: [[Option<Piece>; NUM_COLS]; NUM_ROWS],
+    pub bitboards: Bitboards,
     pub message: String,
     pub history: Vec<String>,
+    pub state: State,
+    pub positions: Vec<u64>,
+    pub records: Vec<MoveRecord>,
+    pub undone: Vec<MoveRecord>,
+    pub hash: u64,
+    pub clock: Option<Clock>,
+}
+
+impl Default for Board {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Board {
     /// Fills board with `None`
     pub fn empty() -> Board {
-        Board {
+        let mut board = Board {
             grid: Default::default(),
+            bitboards: Bitboards::new(),
             message: String::new(),
             history: Vec::new(),
-        }
+            state: State::new(),
+            positions: Vec::new(),
+            records: Vec::new(),
+            undone: Vec::new(),
+            hash: 0,
+            clock: None,
+        };
+        board.hash = zobrist::state(&board);
+        board
     }
 
     /// Sets up board in starting position
@@ -54,73 +89,53 @@ impl Board {
         let pawns = ['♙'; NUM_COLS];
 
         for y in [0, NUM_ROWS - 1] {
-            for x in 0..NUM_COLS.min(pieces.len()) {
-                board.place_piece(x, y, pieces[x], y == 0, 0);
+            for (x, icon) in pieces.iter().enumerate().take(NUM_COLS) {
+                board.place_piece(x, y, *icon, y == 0, 0);
             }
         }
 
         for y in [1, NUM_ROWS - 2] {
-            for x in 0..NUM_COLS.min(pawns.len()) {
-                board.place_piece(x, y, pawns[x], y == 1, 0);
+            for (x, icon) in pawns.iter().enumerate() {
+                board.place_piece(x, y, *icon, y == 1, 0);
             }
         }
 
-        return board;
+        board.grant_castling();
+        board
     }
 
-    /// Creates a random chess960 board
-    pub fn new_random() -> Board {
+    /// Creates a chess960 board from its number in the standard numbering
+    /// * `id` - 0 to 959, where 518 is the usual starting position; larger numbers wrap around
+    pub fn new_chess960(id: u16) -> Board {
         let mut board = Self::empty();
+        let pieces = start::chess960(id);
+        board.place_back_ranks(&pieces, &pieces, true);
+        board
+    }
 
-        // king must be between the rooks
-        let king = rand::thread_rng().gen_range(1..(NUM_COLS - 1));
-        let rook_1 = rand::thread_rng().gen_range(0..king);
-        let rook_2 = rand::thread_rng().gen_range((king + 1)..NUM_COLS);
-
-        // bishops must be on opposite colours
-        let mut light: Vec<usize> = Vec::new();
-        let mut dark: Vec<usize> = Vec::new();
-        for i in 0..NUM_COLS {
-            if i == king || i == rook_1 || i == rook_2 {
-                continue;
-            }
-
-            match i % 2 == 0 {
-                true => light.push(i),
-                false => dark.push(i),
-            }
-        }
-
-        let bishop_1 = light.remove(rand::thread_rng().gen_range(0..light.len()));
-        let bishop_2 = dark.remove(rand::thread_rng().gen_range(0..dark.len()));
-
-        // randomly assign the remaining
-        let mut remaining = light;
-        remaining.extend(dark);
-        let queen = remaining.remove(rand::thread_rng().gen_range(0..remaining.len()));
-        let knight_1 = remaining.remove(rand::thread_rng().gen_range(0..remaining.len()));
-        let knight_2 = remaining.remove(rand::thread_rng().gen_range(0..remaining.len()));
+    /// Creates a random chess960 board
+    /// * `rng` - a seeded generator gives the same position each time
+    pub fn new_random(rng: &mut impl Rng) -> Board {
+        Self::new_chess960(rng.gen_range(0..CHESS960_POSITIONS))
+    }
 
-        for y in [0, NUM_ROWS - 1] {
-            board.place_piece(bishop_1, y, '♗', y == 0, 0);
-            board.place_piece(bishop_2, y, '♗', y == 0, 0);
-            board.place_piece(king, y, '♔', y == 0, 0);
-            board.place_piece(knight_1, y, '♘', y == 0, 0);
-            board.place_piece(knight_2, y, '♘', y == 0, 0);
-            board.place_piece(queen, y, '♕', y == 0, 0);
-            board.place_piece(rook_1, y, '♖', y == 0, 0);
-            board.place_piece(rook_2, y, '♖', y == 0, 0);
-        }
+    /// Finds the chess960 number of the back ranks, if both sides have the same starting pieces
+    pub fn chess960_id(&self) -> Option<u16> {
+        let rank = |y: usize, white: bool| -> Option<Vec<char>> {
+            self.grid[y]
+                .iter()
+                .map(|square| match square {
+                    Some(piece) if piece.white == white => Some(piece.icon),
+                    _ => None,
+                })
+                .collect()
+        };
 
-        // pawns remain the same
-        let pawns = ['♙'; NUM_COLS];
-        for y in [1, NUM_ROWS - 2] {
-            for x in 0..NUM_COLS.min(pawns.len()) {
-                board.place_piece(x, y, pawns[x], y == 1, 0);
-            }
+        let white = rank(0, true)?;
+        if rank(NUM_ROWS - 1, false)? != white {
+            return None;
         }
-
-        return board;
+        (0..CHESS960_POSITIONS).find(|id| start::chess960(*id) == white[..])
     }
 
     /// Sets up board from a vector of piece data tuples
@@ -130,18 +145,64 @@ impl Board {
         for (x, y, icon, white) in pieces {
             board.place_piece(*x, *y, *icon, *white, 0);
         }
-        return board;
+        board.grant_castling();
+        board
+    }
+
+    /// Lets the outermost rook on each side of the king castle, like `KQkq` in FEN
+    pub(crate) fn grant_castling(&mut self) {
+        self.hash ^= zobrist::state(self);
+        for (white, rank) in [(true, 0), (false, NUM_ROWS - 1)] {
+            let pieces: Vec<&Piece> = self.grid[rank]
+                .iter()
+                .flatten()
+                .filter(|piece| piece.white == white)
+                .collect();
+            let king = match pieces.iter().find(|piece| piece.id == Id::King) {
+                Some(king) => king.position.x,
+                None => continue,
+            };
+
+            let rooks: Vec<usize> = pieces
+                .iter()
+                .filter(|piece| piece.id == Id::Rook)
+                .map(|piece| piece.position.x)
+                .collect();
+            let queenside = rooks.iter().filter(|x| **x < king).min();
+            let kingside = rooks.iter().filter(|x| **x > king).max();
+            let rooks: Vec<Coordinate> = [queenside, kingside]
+                .into_iter()
+                .flatten()
+                .map(|x| Coordinate { x: *x, y: rank })
+                .collect();
+            self.state.castling.extend(rooks);
+        }
+        self.hash ^= zobrist::state(self);
     }
 
     /// Sets a single piece at (x, y)
     pub fn place_piece(&mut self, x: usize, y: usize, icon: char, white: bool, moves: usize) {
         let piece = Piece::new(x, y, icon, white, moves);
         match piece {
-            Ok(piece) => self.grid[y][x] = Some(piece),
+            Ok(piece) => {
+                self.remove_piece(x, y);
+                self.hash ^= zobrist::piece(&piece.id, white, bitboard::square(x, y));
+                self.bitboards.set(bitboard::square(x, y), &piece.id, white);
+                self.grid[y][x] = Some(piece);
+            }
             Err(_) => eprintln!("Could not place {} at ({}, {})", icon, x, y),
         }
     }
 
+    /// Clears the piece at (x, y), if there is one
+    pub fn remove_piece(&mut self, x: usize, y: usize) {
+        if let Some(piece) = &self.grid[y][x] {
+            self.hash ^= zobrist::piece(&piece.id, piece.white, bitboard::square(x, y));
+        }
+        self.bitboards.clear(bitboard::square(x, y));
+        self.grid[y][x] = None;
+    }
+
     /// Prints out a specific tile, with A1 as (0, 0)
     fn show_tile(&self, x: usize, y: usize) {
         let tile = TILE_COLOURS[(x + y) % 2];
@@ -177,7 +238,21 @@ impl Board {
             }
 
             // clear current background colour
-            println!("\u{001b}[0m");
+            print!("\u{001b}[0m");
+
+            // each player's clock goes beside their back rank
+            match &self.clock {
+                Some(clock) if i == 0 || i == NUM_ROWS - 1 => {
+                    let side = if i == 0 { !white } else { white };
+                    let time = clock::format(clock.remaining(side));
+                    match clock.running() == Some(side) {
+                        true => print!("  \u{001b}[4m{}\u{001b}[24m", time),
+                        false => print!("  {}", time),
+                    }
+                }
+                _ => (),
+            }
+            println!();
         }
 
         // print column letters
@@ -195,16 +270,8 @@ impl Board {
         println!("\u{001b}[0m");
     }
 
-    /// Simple loading bar to be shown between turns
-    pub fn show_loading_bar(&self) {
-        println!();
-        for i in 1..=3 {
-            println!("\u{001b}[F{}", LOADING_ICON.repeat(i));
-            thread::sleep(INTERVAL);
-        }
-    }
-
     /// Standardises the input string
+    #[allow(clippy::needless_return)]
     fn sanitise_input(input: &str) -> String {
         // these characters don't convery any additional information
         // x, : for captures (e.g. Bxe5, B:e5 or Be5:)
@@ -226,6 +293,7 @@ impl Board {
 
     /// Checks what the move promotes to
     /// * returns `None` if it's not a promotion move
+    #[allow(clippy::needless_return)]
     fn promote_to(input: &str) -> Option<char> {
         for (letter, icon) in [('B', '♗'), ('N', '♘'), ('Q', '♕'), ('R', '♖')] {
             if input.ends_with(letter) {
@@ -254,14 +322,23 @@ impl Board {
 
     /// Converts a coordinate from alphanumeric grid to 0-indexed coordinates
     fn target_position(input: &str) -> Result<Coordinate, Error> {
-        if input.len() < 2 {
-            return Err(Error::IndexOutOfRange);
+        if input.len() < 2 || !input.is_char_boundary(input.len() - 2) {
+            return Err(Self::notation(input, 0, input.len()));
         }
 
         // last 2 chars of move refers to the destination
         let index = input.len() - 2;
-        let target = Coordinate::from_alphanumeric(&input[index..])?;
-        Ok(target)
+        Coordinate::from_alphanumeric(&input[index..])
+            .map_err(|_| Self::notation(input, index, input.len()))
+    }
+
+    /// Error for the part of a move between `start` and `end` that couldn't be read
+    fn notation(input: &str, start: usize, end: usize) -> Error {
+        Error::Notation {
+            input: String::from(input),
+            token: input.get(start..end).unwrap_or(input).to_string(),
+            position: input[..start.min(input.len())].chars().count(),
+        }
     }
 
     // Returns the piece to move, the position to move to, and if the move is a promotion
@@ -269,8 +346,8 @@ impl Board {
         input: &str,
         white: bool,
     ) -> Result<(Id, Coordinate, Option<char>), Error> {
-        let promotion = Self::promote_to(&input);
-        let id = Self::piece_id(&input)?;
+        let promotion = Self::promote_to(input);
+        let id = Self::piece_id(input)?;
 
         // if the move is a promotion,
         // remove the last letter so that the target position is the last 2 characters
@@ -283,16 +360,12 @@ impl Board {
         // only allow promotion if its a pawn move to the correct rank
         let promotion_rank = if white { 7 } else { 0 };
         if promotion.is_some() && (id != Id::Pawn || target.y != promotion_rank) {
-            return Err(Error::InvalidMove {
-                message: String::from("is not a valid promotion"),
-            });
+            return Err(MoveError::InvalidPromotion.into());
         }
 
         // must promote when on promotion rank
         if promotion.is_none() && id == Id::Pawn && target.y == promotion_rank {
-            return Err(Error::InvalidMove {
-                message: String::from("is not valid because promotion is forced"),
-            });
+            return Err(MoveError::PromotionRequired.into());
         }
 
         Ok((id, target, promotion))
@@ -307,27 +380,31 @@ impl Board {
         // ignore pawn moves that are of length 2
         if id == &Id::Pawn && input.len() > 2 {
             // first letter identifies the column
-            x = input.chars().nth(0).unwrap() as usize - 97;
+            let file = input.chars().nth(0).unwrap();
+            if !('a'..='h').contains(&file) {
+                return Err(Self::notation(input, 0, file.len_utf8()));
+            }
+            x = file as usize - 97;
         }
 
         // all other disambiguations for other pieces
         if id != &Id::Pawn && input.len() > 3 {
             // skip the piece letter to get the identifiers
-            let coordinates: Vec<char> = input[1..(input.len() - 2)].chars().collect();
+            let end = input.len() - 2;
+            let coordinates: Vec<char> = input[1..end].chars().collect();
+            let file = |c: char| ('a'..='h').contains(&c);
+            let rank = |c: char| ('1'..='8').contains(&c);
 
-            if coordinates.len() == 1 {
+            match coordinates.as_slice() {
                 // decide whether its the column or row identifier
-                if coordinates[0].is_alphabetic() && coordinates[0].is_lowercase() {
-                    x = coordinates[0] as usize - 97;
-                } else {
-                    y = coordinates[0] as usize - 49;
+                [c] if file(*c) => x = *c as usize - 97,
+                [c] if rank(*c) => y = *c as usize - 49,
+                [a, b] if file(*a) && rank(*b) => {
+                    x = *a as usize - 97;
+                    y = *b as usize - 49;
                 }
-            } else if coordinates.len() == 2 {
-                x = coordinates[0] as usize - 97;
-                y = coordinates[1] as usize - 49;
-            } else {
                 // there shouldn't be more than 2 identifiers
-                return Err(Error::InvalidArgument);
+                _ => return Err(Self::notation(input, 1, end)),
             }
         }
 
@@ -343,25 +420,32 @@ impl Board {
     ///     - Q for queen
     ///     - R for rook
     ///
-    /// * Returns a `Piece` and a `Coordinate` to move to
-    pub fn parse_move(&self, input: &str, white: bool) -> Result<MoveType, Error> {
+    /// * Returns a `Piece` and a `Coordinate` to move to, for the side to move
+    pub fn parse_move(&self, input: &str) -> Result<MoveType, Error> {
+        let white = self.state.white;
+        let input = Self::sanitise_input(input);
         if input.len() < 2 {
-            return Err(Error::InvalidArgument);
+            return Err(Self::notation(&input, 0, input.len()));
         }
-        let input = Self::sanitise_input(input);
 
         // handle castling separtely
         if input == "O-O" || input == "O-O-O" {
             let kingside = input == "O-O";
-            return match MoveChecker::castle(&self, kingside, white) {
-                Some((king_x, rook_x)) => Ok(MoveType::Castle {
-                    king_x,
-                    rook_x,
-                    kingside,
-                }),
-                None => Err(Error::InvalidMove {
-                    message: String::from("cannot castle"),
-                }),
+            return match MoveChecker::castle(self, kingside, white) {
+                Some((king_x, rook_x)) => {
+                    let move_type = MoveType::Castle {
+                        king_x,
+                        rook_x,
+                        kingside,
+                    };
+
+                    // in chess960 the rook can be shielding the king's destination
+                    match self.exposes_king(&move_type, white) {
+                        true => Err(self.uncastleable(kingside, white).into()),
+                        false => Ok(move_type),
+                    }
+                }
+                None => Err(self.uncastleable(kingside, white).into()),
             };
         }
 
@@ -370,33 +454,25 @@ impl Board {
 
         if id == Id::Pawn {
             let from = if x != AMBIGUOUS { Some(x) } else { None };
-            match MoveChecker::en_passant(&self, from, &target, white) {
-                Some((x, y)) => {
-                    let from = Coordinate::new(x, y)?;
-                    let capture = Coordinate::new(target.x, y)?;
-
-                    // check if the move will put the king in check with a test board
-                    let mut test_board = self.clone();
-                    test_board.grid[from.y][from.x] = None;
-                    test_board.place_piece(target.x, target.y, '♙', white, 0);
-                    test_board.grid[capture.y][capture.x] = None;
-                    return match MoveChecker::in_check(&test_board, white) {
-                        true => Err(Error::InvalidMove {
-                            message: String::from("puts the king in check"),
-                        }),
-                        false => Ok(MoveType::EnPassant {
-                            from,
-                            target,
-                            capture,
-                        }),
-                    };
-                }
-                None => (),
-            };
+            if let Some((x, y)) = MoveChecker::en_passant(self, from, &target, white) {
+                let from = Coordinate::new(x, y)?;
+                let capture = Coordinate::new(target.x, y)?;
+                let move_type = MoveType::EnPassant {
+                    from,
+                    target,
+                    capture,
+                };
+
+                return match self.exposes_king(&move_type, white) {
+                    true => Err(self.exposed(&move_type, white).into()),
+                    false => Ok(move_type),
+                };
+            }
         }
 
         // check if there is any remaining ambiguity
         let mut possible_move: Option<(&Piece, Coordinate)> = None;
+        let mut candidates: Vec<Coordinate> = Vec::new();
 
         // searching every square in an 8 x 8 grid isn't the most efficient way,
         // but given the small size it shouldn't be a significant cost to performance
@@ -408,7 +484,7 @@ impl Board {
                     Some(piece) => {
                         if piece.id == id
                             && piece.white == white
-                            && checker.can_move(&self, piece, &target)
+                            && checker.can_move(self, piece, &target)
                         {
                             // check for ambiguity
                             if (x != AMBIGUOUS && piece.position.x != x)
@@ -419,12 +495,9 @@ impl Board {
 
                             // if a move has already been found,
                             // then there shouldn't be another possibility
+                            candidates.push(piece.position);
                             match possible_move {
-                                Some(_) => {
-                                    return Err(Error::InvalidMove {
-                                        message: String::from("is ambiguous"),
-                                    })
-                                }
+                                Some(_) => (),
                                 None => possible_move = Some((piece, target)),
                             }
                         }
@@ -434,26 +507,123 @@ impl Board {
             }
         }
 
+        if candidates.len() > 1 {
+            return Err(MoveError::Ambiguous {
+                id,
+                target,
+                candidates,
+            }
+            .into());
+        }
+
         // check if a move has been found
-        return match possible_move {
+        match possible_move {
             Some((piece, target)) => {
-                // check if the move will put the king in check with a test board
-                let mut test_board = self.clone();
-                test_board.grid[piece.position.y][piece.position.x] = None;
-                test_board.place_piece(target.x, target.y, piece.icon, white, piece.moves);
-                match MoveChecker::in_check(&test_board, white) {
-                    true => Err(Error::InvalidMove {
-                        message: String::from("puts the king in check"),
-                    }),
-                    false => Ok(MoveType::Normal {
+                let move_type = MoveType::Normal {
+                    piece: piece.clone(),
+                    target,
+                    promotion,
+                };
+
+                match self.exposes_king(&move_type, white) {
+                    true => Err(self.exposed(&move_type, white).into()),
+                    false => Ok(move_type),
+                }
+            }
+            None => {
+                let x = if x != AMBIGUOUS { Some(x) } else { None };
+                let y = if y != AMBIGUOUS { Some(y) } else { None };
+                Err(self.unreachable(&id, target, white, x, y).into())
+            }
+        }
+    }
+
+    /// Checks if making a move would leave the mover's king in check
+    /// * only the bitboards are copied, so this is cheap enough to call for every move
+    fn exposes_king(&self, move_type: &MoveType, white: bool) -> bool {
+        let mut bitboards = self.bitboards;
+        bitboards.apply(move_type, white);
+        bitboards.in_check(white)
+    }
+
+    /// Generates every legal move for the side to move
+    /// * promotions are listed once for each piece that can be promoted to
+    /// * includes castling and en passant
+    pub fn legal_moves(&self) -> Vec<MoveType> {
+        let white = self.state.white;
+        let mut moves: Vec<MoveType> = Vec::new();
+        let promotion_rank = if white { NUM_ROWS - 1 } else { 0 };
+
+        let own = self.bitboards.colour(white);
+        let enemy = self.bitboards.colour(!white);
+        let occupied = own | enemy;
+        for from in bitboard::squares(own) {
+            let piece = match &self.grid[from / NUM_COLS][from % NUM_COLS] {
+                Some(piece) => piece,
+                None => continue,
+            };
+
+            // pawns can only move diagonally when taking
+            let targets = match piece.id {
+                Id::Pawn => {
+                    bitboard::pawn_pushes(white, from, occupied)
+                        | (attacks::piece(&piece.id, white, from, occupied) & enemy)
+                }
+                _ => attacks::piece(&piece.id, white, from, occupied) & !own,
+            };
+
+            for to in bitboard::squares(targets) {
+                let target = Coordinate {
+                    x: to % NUM_COLS,
+                    y: to / NUM_COLS,
+                };
+
+                if piece.id == Id::Pawn && target.y == promotion_rank {
+                    for icon in PROMOTIONS {
+                        moves.push(MoveType::Normal {
+                            piece: piece.clone(),
+                            target,
+                            promotion: Some(icon),
+                        });
+                    }
+                } else {
+                    moves.push(MoveType::Normal {
                         piece: piece.clone(),
                         target,
-                        promotion,
-                    }),
+                        promotion: None,
+                    });
                 }
             }
-            None => Err(Error::InvalidArgument),
-        };
+        }
+
+        // en passant captures land on the rank behind the capturing pawn
+        let rank = if white { EN_PASSANT[0] } else { EN_PASSANT[1] };
+        let target_rank = if white { rank + 1 } else { rank - 1 };
+        for x in 0..NUM_COLS {
+            let target = Coordinate { x, y: target_rank };
+            for from in [x.wrapping_sub(1), x + 1] {
+                if MoveChecker::en_passant(self, Some(from), &target, white).is_some() {
+                    moves.push(MoveType::EnPassant {
+                        from: Coordinate { x: from, y: rank },
+                        target,
+                        capture: Coordinate { x, y: rank },
+                    });
+                }
+            }
+        }
+
+        for kingside in [true, false] {
+            if let Some((king_x, rook_x)) = MoveChecker::castle(self, kingside, white) {
+                moves.push(MoveType::Castle {
+                    king_x,
+                    rook_x,
+                    kingside,
+                })
+            }
+        }
+
+        moves.retain(|move_type| !self.exposes_king(move_type, white));
+        moves
     }
 
     /// Moves a piece to the target position
@@ -470,7 +640,7 @@ impl Board {
         let moves = piece.moves + 1;
 
         // move piece
-        self.grid[piece.position.y][piece.position.x] = None;
+        self.remove_piece(piece.position.x, piece.position.y);
         self.place_piece(x, y, icon, white, moves);
     }
 
@@ -485,10 +655,10 @@ impl Board {
         let king_target = files[0];
         let rook_target = files[1];
 
-        // move pieces
-        self.grid[rank][king_x] = None;
+        // clear both squares first, since in chess960 either piece can land on the other's square
+        self.remove_piece(king_x, rank);
+        self.remove_piece(rook_x, rank);
         self.place_piece(king_target, rank, '♔', white, 1);
-        self.grid[rank][rook_x] = None;
         self.place_piece(rook_target, rank, '♖', white, 1);
     }
 
@@ -500,63 +670,217 @@ impl Board {
         capture: Coordinate,
         white: bool,
     ) {
-        self.grid[from.y][from.x] = None;
+        self.remove_piece(from.x, from.y);
         self.place_piece(target.x, target.y, '♙', white, 0);
-        self.grid[capture.y][capture.x] = None;
+        self.remove_piece(capture.x, capture.y);
     }
 
-    /// Moves a piece based on `input`
-    /// * Returns `true` if the move is valid, `false` if not
-    pub fn make_move(&mut self, input: &str, white: bool) -> bool {
+    /// Copies the position without the game record, which would otherwise be copied into every
+    /// position looked at when searching ahead
+    pub fn position(&self) -> Board {
+        let mut board = self.clone();
+        board.message.clear();
+        board.history.clear();
+        board.positions.clear();
+        board.records.clear();
+        board.undone.clear();
+        board.clock = None;
+        board
+    }
+
+    /// Updates the grid and [State] for any type of move without validating it
+    pub(crate) fn apply_move(&mut self, move_type: &MoveType, white: bool) {
+        let state = self.state.after(self, move_type, white);
+        self.hash ^= zobrist::state(self);
+        match move_type.clone() {
+            MoveType::Normal {
+                piece,
+                target,
+                promotion,
+            } => self.make_normal_move(piece, target, promotion),
+            MoveType::Castle {
+                king_x,
+                rook_x,
+                kingside,
+            } => self.castle(king_x, rook_x, kingside, white),
+            MoveType::EnPassant {
+                from,
+                target,
+                capture,
+            } => self.en_passant(from, target, capture, white),
+        };
+        self.state = state;
+        self.hash ^= zobrist::state(self);
+    }
+
+    /// Makes a move that has already been generated, e.g. from [Board::legal_moves]
+    pub fn play(&mut self, move_type: &MoveType) {
+        let white = self.state.white;
         self.message.clear();
+        self.undone.clear();
+        self.commit_move(move_type, white, self.to_san(move_type));
+    }
 
-        // check if move is valid first
-        match self.parse_move(input, white) {
-            Ok(move_type) => {
-                match move_type {
-                    MoveType::Normal {
-                        piece,
-                        target,
-                        promotion,
-                    } => self.make_normal_move(piece, target, promotion),
-                    MoveType::Castle {
-                        king_x,
-                        rook_x,
-                        kingside,
-                    } => self.castle(king_x, rook_x, kingside, white),
-                    MoveType::EnPassant {
-                        from,
-                        target,
-                        capture,
-                    } => self.en_passant(from, target, capture, white),
-                };
+    /// Makes a validated move and records it for the draw rules
+    /// * `san` - the move in standard algebraic notation, added to `history`
+    fn commit_move(&mut self, move_type: &MoveType, white: bool, san: String) {
+        let record = self.record(move_type, white, &san);
+
+        // the starting position counts towards repetitions too
+        if self.positions.is_empty() {
+            self.positions.push(self.hash);
+        }
+
+        self.apply_move(move_type, white);
+        if let Some(clock) = &mut self.clock {
+            clock.press(white);
+        }
+        self.history.push(san);
+        self.positions.push(self.hash);
+        self.records.push(record);
+    }
 
-                self.history.push(String::from(input));
-                return true;
+    /// Saves the pieces a move is about to disturb, along with the game record
+    fn record(&self, move_type: &MoveType, white: bool, san: &str) -> MoveRecord {
+        let piece_at = |x: usize, y: usize| self.grid[y][x].clone();
+        let rank = if white { 0 } else { NUM_ROWS - 1 };
+        let (moved, rook, captured) = match move_type {
+            MoveType::Normal { piece, target, .. } => {
+                (Some(piece.clone()), None, piece_at(target.x, target.y))
             }
-            Err(error) => {
-                let error_message = match error {
-                    Error::InvalidMove { message } => message,
-                    _ => String::from("is not a valid move"),
+            MoveType::Castle { king_x, rook_x, .. } => {
+                (piece_at(*king_x, rank), piece_at(*rook_x, rank), None)
+            }
+            MoveType::EnPassant { from, capture, .. } => (
+                piece_at(from.x, from.y),
+                None,
+                piece_at(capture.x, capture.y),
+            ),
+        };
+
+        // validated moves always have a piece to move
+        MoveRecord {
+            move_type: move_type.clone(),
+            white,
+            moved: moved.unwrap(),
+            rook,
+            captured,
+            san: String::from(san),
+            state: self.state.clone(),
+            positions: self.positions.len(),
+        }
+    }
+
+    /// Takes back the last move
+    /// * Returns `false` if there are no moves to undo
+    pub fn undo_move(&mut self) -> bool {
+        let record = match self.records.pop() {
+            Some(record) => record,
+            None => return false,
+        };
+
+        self.hash ^= zobrist::state(self);
+
+        // clear every square the move put a piece on
+        let rank = if record.white { 0 } else { NUM_ROWS - 1 };
+        match &record.move_type {
+            MoveType::Normal { target, .. } | MoveType::EnPassant { target, .. } => {
+                self.remove_piece(target.x, target.y);
+            }
+            MoveType::Castle { kingside, .. } => {
+                let files = if *kingside {
+                    KINGSIDE_CASTLE
+                } else {
+                    QUEENSIDE_CASTLE
                 };
-                self.message = format!("{}{} {}", WARNING_COLOUR, input, error_message);
-                return false;
+                for x in files {
+                    self.remove_piece(x, rank);
+                }
             }
+        }
+
+        // pieces remember where they were
+        let pieces = [
+            Some(record.moved.clone()),
+            record.rook.clone(),
+            record.captured.clone(),
+        ];
+        for piece in pieces.into_iter().flatten() {
+            let position = piece.position;
+            self.place_piece(position.x, position.y, piece.icon, piece.white, piece.moves);
+        }
+
+        self.history.pop();
+        self.positions.truncate(record.positions);
+        self.state = record.state.clone();
+        self.hash ^= zobrist::state(self);
+        self.undone.push(record);
+        true
+    }
+
+    /// Makes the last undone move again
+    /// * Returns `false` if there are no moves to redo
+    pub fn redo(&mut self) -> bool {
+        let record = match self.undone.pop() {
+            Some(record) => record,
+            None => return false,
         };
+
+        self.commit_move(&record.move_type, record.white, record.san);
+        true
+    }
+
+    /// Moves a piece based on `input`
+    /// * Returns the reason if the move can't be made
+    pub fn try_move(&mut self, input: &str) -> Result<(), Error> {
+        let white = self.state.white;
+        let move_type = self.parse_move(input)?;
+        self.undone.clear();
+        self.commit_move(&move_type, white, self.to_san(&move_type));
+        Ok(())
+    }
+
+    /// Moves a piece based on `input`
+    /// * Returns `true` if the move is valid, `false` if not
+    pub fn make_move(&mut self, input: &str) -> bool {
+        self.message.clear();
+
+        match self.try_move(input) {
+            Ok(()) => true,
+            Err(error) => {
+                self.message = format!("{}{} {}", WARNING_COLOUR, input, error.reason());
+                false
+            }
+        }
     }
 
-    /// Displays the winner once one player reaches checkmate
-    pub fn game_over(&mut self, white: bool) -> bool {
-        if MoveChecker::checkmate(self, !white) {
-            self.message = format!(
-                "\u{001b}[5m{} has won!\u{001b}[0m",
-                if white { "White" } else { "Black" }
-            );
+    /// Checks if the game has ended with the side to move
+    pub fn game_result(&self) -> Option<GameResult> {
+        GameResult::from_board(self)
+    }
 
-            self.show(white);
-            return true;
+    /// Displays the result if the player to move has run out of time
+    pub fn flagged(&mut self) -> bool {
+        match self.game_result() {
+            Some(result) if result.termination == Termination::Timeout => {
+                self.message = format!("\u{001b}[5m{}\u{001b}[0m", result);
+                self.show(self.state.white);
+                true
+            }
+            _ => false,
         }
+    }
 
-        return false;
+    /// Displays the winner or the reason for a draw once the game has ended
+    /// * the board is shown from the side of the player who just moved
+    pub fn game_over(&mut self) -> bool {
+        match self.game_result() {
+            Some(result) => {
+                self.message = format!("\u{001b}[5m{}\u{001b}[0m", result);
+                self.show(!self.state.white);
+                true
+            }
+            None => false,
+        }
     }
 }
@@ -0,0 +1,224 @@
+use crate::board::{Board, NUM_ROWS};
+use crate::pgn::Pgn;
+use crate::pieces::moves::MoveType;
+use crate::pieces::Id;
+use crate::Error;
+use rand::Rng;
+use std::collections::HashMap;
+use std::fs;
+
+// every entry takes up 16 bytes: key, move, weight and learning data
+const ENTRY_SIZE: usize = 16;
+
+/// Plies of each game added by [Book::build] unless told otherwise
+pub const DEFAULT_PLIES: usize = 20;
+
+/// A move from a Polyglot opening book
+/// * `key` - Zobrist hash of the position, see [crate::zobrist]
+/// * `raw` - move packed into 16 bits, see [encode]
+/// * `weight` - how often the move should be chosen, relative to the others in the position
+/// * `learn` - left for other programs to use, kept as it is
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Entry {
+    pub key: u64,
+    pub raw: u16,
+    pub weight: u16,
+    pub learn: u32,
+}
+
+/// Opening moves in the Polyglot format, sorted by key so positions can be found by binary search
+pub struct Book {
+    pub entries: Vec<Entry>,
+}
+
+impl Book {
+    /// Reads a `.bin` book
+    pub fn open(path: &str) -> Result<Book, Error> {
+        match fs::read(path) {
+            Ok(bytes) => Self::from_bytes(&bytes),
+            Err(error) => Err(Self::error(&error.to_string())),
+        }
+    }
+
+    /// Reads the entries of a book, which are stored big-endian
+    pub fn from_bytes(bytes: &[u8]) -> Result<Book, Error> {
+        if !bytes.len().is_multiple_of(ENTRY_SIZE) {
+            return Err(Self::error("book is not made of 16 byte entries"));
+        }
+
+        let mut entries: Vec<Entry> = bytes
+            .chunks_exact(ENTRY_SIZE)
+            .map(|chunk| Entry {
+                key: u64::from_be_bytes(chunk[0..8].try_into().unwrap()),
+                raw: u16::from_be_bytes(chunk[8..10].try_into().unwrap()),
+                weight: u16::from_be_bytes(chunk[10..12].try_into().unwrap()),
+                learn: u32::from_be_bytes(chunk[12..16].try_into().unwrap()),
+            })
+            .collect();
+
+        // books should already be sorted, but searching relies on it
+        entries.sort_by_key(|entry| entry.key);
+        Ok(Book { entries })
+    }
+
+    /// Writes the entries in the `.bin` format
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(self.entries.len() * ENTRY_SIZE);
+        for entry in &self.entries {
+            bytes.extend(entry.key.to_be_bytes());
+            bytes.extend(entry.raw.to_be_bytes());
+            bytes.extend(entry.weight.to_be_bytes());
+            bytes.extend(entry.learn.to_be_bytes());
+        }
+        bytes
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), Error> {
+        fs::write(path, self.to_bytes()).map_err(|error| Self::error(&error.to_string()))
+    }
+
+    fn error(message: &str) -> Error {
+        Error::InvalidBook {
+            message: String::from(message),
+        }
+    }
+
+    /// Entries for a position, found by binary search
+    pub fn entries(&self, key: u64) -> &[Entry] {
+        let start = self.entries.partition_point(|entry| entry.key < key);
+        let end = self.entries.partition_point(|entry| entry.key <= key);
+        &self.entries[start..end]
+    }
+
+    /// Legal moves the book has for a position, with their weights
+    /// * moves that aren't legal are left out, in case of hash collisions
+    pub fn moves(&self, board: &Board) -> Vec<(MoveType, u16)> {
+        let white = board.state.white;
+        let legal = board.legal_moves();
+        self.entries(board.hash)
+            .iter()
+            .filter_map(|entry| {
+                legal
+                    .iter()
+                    .find(|move_type| encode(move_type, white) == entry.raw)
+                    .map(|move_type| (move_type.clone(), entry.weight))
+            })
+            .collect()
+    }
+
+    /// Picks a book move at random, with more weight making a move more likely
+    /// * returns `None` once the game has left the book
+    pub fn choose(&self, board: &Board, rng: &mut impl Rng) -> Option<MoveType> {
+        let moves: Vec<(MoveType, u16)> = self
+            .moves(board)
+            .into_iter()
+            .filter(|(_, weight)| *weight > 0)
+            .collect();
+        let total: u32 = moves.iter().map(|(_, weight)| *weight as u32).sum();
+        if total == 0 {
+            return None;
+        }
+
+        let mut pick = rng.gen_range(0..total);
+        for (move_type, weight) in moves {
+            if pick < weight as u32 {
+                return Some(move_type);
+            }
+            pick -= weight as u32;
+        }
+
+        None
+    }
+
+    /// Makes a book from the opening moves of a collection of games
+    /// * `plies` - how many moves of each game to add
+    /// * moves are weighted by 2 for each win and 1 for each draw, for the side that played them
+    /// * games stop being added at their first illegal move
+    pub fn build(games: &[Pgn], plies: usize) -> Book {
+        let mut weights: HashMap<(u64, u16), u32> = HashMap::new();
+        for game in games {
+            let mut board = match game.tag("FEN").map(Board::from_fen) {
+                Some(Ok(board)) => board,
+                Some(Err(_)) => continue,
+                None => Board::new(),
+            };
+
+            for input in game.moves.iter().take(plies) {
+                let white = board.state.white;
+                let move_type = match board.parse_move(input) {
+                    Ok(move_type) => move_type,
+                    Err(_) => break,
+                };
+
+                let score = match (game.result.as_str(), white) {
+                    ("1-0", true) | ("0-1", false) => 2,
+                    ("1/2-1/2", _) => 1,
+                    _ => 0,
+                };
+                *weights
+                    .entry((board.hash, encode(&move_type, white)))
+                    .or_insert(0) += score;
+
+                board.play(&move_type);
+            }
+        }
+
+        // weights have to fit in 16 bits, so positions with too many games are scaled down
+        let mut largest: HashMap<u64, u32> = HashMap::new();
+        for ((key, _), weight) in &weights {
+            let largest = largest.entry(*key).or_insert(0);
+            *largest = (*largest).max(*weight);
+        }
+
+        let mut entries: Vec<Entry> = weights
+            .into_iter()
+            .filter(|(_, weight)| *weight > 0)
+            .map(|((key, raw), weight)| {
+                let scale = largest[&key].div_ceil(u16::MAX as u32);
+                Entry {
+                    key,
+                    raw,
+                    weight: (weight / scale).max(1) as u16,
+                    learn: 0,
+                }
+            })
+            .collect();
+
+        // most played moves first within each position
+        entries.sort_by_key(|entry| (entry.key, u16::MAX - entry.weight, entry.raw));
+        Book { entries }
+    }
+}
+
+/// Packs a move into 16 bits, from the lowest: target file, target rank, starting file, starting
+/// rank and promotion
+/// * castling is written as the king taking its own rook
+pub fn encode(move_type: &MoveType, white: bool) -> u16 {
+    let (from, target, promotion) = match move_type {
+        MoveType::Normal {
+            piece,
+            target,
+            promotion,
+        } => {
+            let promotion = match promotion.map(Id::from_char) {
+                Some(Ok(Id::Knight)) => 1,
+                Some(Ok(Id::Bishop)) => 2,
+                Some(Ok(Id::Rook)) => 3,
+                Some(Ok(Id::Queen)) => 4,
+                _ => 0,
+            };
+            (
+                (piece.position.x, piece.position.y),
+                (target.x, target.y),
+                promotion,
+            )
+        }
+        MoveType::Castle { king_x, rook_x, .. } => {
+            let rank = if white { 0 } else { NUM_ROWS - 1 };
+            ((*king_x, rank), (*rook_x, rank), 0)
+        }
+        MoveType::EnPassant { from, target, .. } => ((from.x, from.y), (target.x, target.y), 0),
+    };
+
+    (target.0 | target.1 << 3 | from.0 << 6 | from.1 << 9 | promotion << 12) as u16
+}
@@ -0,0 +1,206 @@
+use std::fmt;
+use std::time::{Duration, Instant};
+
+// assume a game of this many more moves when only the clock is given
+const DEFAULT_MOVES_TO_GO: u64 = 30;
+
+// time kept back for communicating with the GUI or printing the board
+const MOVE_OVERHEAD: Duration = Duration::from_millis(50);
+
+/// Rules for how much time each player gets
+/// * `SuddenDeath` - one amount of time for the whole game
+/// * `Fischer` - `increment` is added after each move
+/// * `Bronstein` - time used on a move is given back, up to `delay`
+/// * `MovesPerPeriod` - `base` is added again after every `moves` moves
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TimeControl {
+    SuddenDeath { base: Duration },
+    Fischer { base: Duration, increment: Duration },
+    Bronstein { base: Duration, delay: Duration },
+    MovesPerPeriod { base: Duration, moves: usize },
+}
+
+impl TimeControl {
+    /// Time each player starts with
+    pub fn base(&self) -> Duration {
+        match self {
+            TimeControl::SuddenDeath { base }
+            | TimeControl::Fischer { base, .. }
+            | TimeControl::Bronstein { base, .. }
+            | TimeControl::MovesPerPeriod { base, .. } => *base,
+        }
+    }
+}
+
+impl fmt::Display for TimeControl {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let minutes = |duration: &Duration| duration.as_secs() / 60;
+        match self {
+            TimeControl::SuddenDeath { base } => write!(f, "{} min", minutes(base)),
+            TimeControl::Fischer { base, increment } => {
+                write!(f, "{} min + {}s", minutes(base), increment.as_secs())
+            }
+            TimeControl::Bronstein { base, delay } => {
+                write!(f, "{} min, {}s delay", minutes(base), delay.as_secs())
+            }
+            TimeControl::MovesPerPeriod { base, moves } => {
+                write!(f, "{} moves in {} min", moves, minutes(base))
+            }
+        }
+    }
+}
+
+/// Time left for both players, with only the side to move's clock running
+/// * `remaining` - indexed by colour, white first, as of when the running clock was started
+/// * `moves` - moves made by each player, for [TimeControl::MovesPerPeriod]
+/// * `running` - the player whose clock is running and when it was started
+#[derive(Clone, Debug)]
+pub struct Clock {
+    pub control: TimeControl,
+    remaining: [Duration; 2],
+    moves: [usize; 2],
+    running: Option<(bool, Instant)>,
+}
+
+impl Clock {
+    /// Sets both clocks to the starting time, without starting either
+    pub fn new(control: TimeControl) -> Clock {
+        Clock {
+            control,
+            remaining: [control.base(); 2],
+            moves: [0; 2],
+            running: None,
+        }
+    }
+
+    fn index(white: bool) -> usize {
+        if white {
+            0
+        } else {
+            1
+        }
+    }
+
+    /// Starts the clock of the player to move
+    pub fn start(&mut self, white: bool) {
+        self.start_at(white, Instant::now());
+    }
+
+    pub fn start_at(&mut self, white: bool, now: Instant) {
+        self.running = Some((white, now));
+    }
+
+    /// Stops whichever clock is running, keeping the time used so far
+    pub fn stop(&mut self) {
+        self.stop_at(Instant::now());
+    }
+
+    pub fn stop_at(&mut self, now: Instant) {
+        if let Some((white, _)) = self.running {
+            self.remaining[Self::index(white)] = self.remaining_at(white, now);
+            self.running = None;
+        }
+    }
+
+    /// Checks whose clock is running
+    pub fn running(&self) -> Option<bool> {
+        self.running.map(|(white, _)| white)
+    }
+
+    /// Time a player has left, counting down while their clock runs
+    pub fn remaining(&self, white: bool) -> Duration {
+        self.remaining_at(white, Instant::now())
+    }
+
+    pub fn remaining_at(&self, white: bool, now: Instant) -> Duration {
+        let remaining = self.remaining[Self::index(white)];
+        match self.running {
+            Some((running, start)) if running == white => {
+                remaining.saturating_sub(now.saturating_duration_since(start))
+            }
+            _ => remaining,
+        }
+    }
+
+    /// Checks if a player has run out of time
+    pub fn flagged(&self, white: bool) -> bool {
+        self.remaining(white).is_zero()
+    }
+
+    /// Stops a player's clock after they move and starts their opponent's
+    /// * returns `false` if they ran out of time first, in which case no time is added
+    pub fn press(&mut self, white: bool) -> bool {
+        self.press_at(white, Instant::now())
+    }
+
+    pub fn press_at(&mut self, white: bool, now: Instant) -> bool {
+        let used = match self.running {
+            Some((running, start)) if running == white => now.saturating_duration_since(start),
+            _ => Duration::ZERO,
+        };
+
+        let i = Self::index(white);
+        let remaining = self.remaining_at(white, now);
+        self.running = Some((!white, now));
+        if remaining.is_zero() {
+            self.remaining[i] = Duration::ZERO;
+            return false;
+        }
+
+        self.moves[i] += 1;
+        self.remaining[i] = remaining
+            + match self.control {
+                TimeControl::SuddenDeath { .. } => Duration::ZERO,
+                TimeControl::Fischer { increment, .. } => increment,
+                TimeControl::Bronstein { delay, .. } => used.min(delay),
+                TimeControl::MovesPerPeriod { base, moves } => match self.moves[i] % moves {
+                    0 => base,
+                    _ => Duration::ZERO,
+                },
+            };
+        true
+    }
+
+    /// Time to spend on the next move, leaving enough for the rest of the game
+    pub fn allocate(&self, white: bool) -> Duration {
+        let (bonus, moves_to_go) = match self.control {
+            TimeControl::SuddenDeath { .. } => (Duration::ZERO, None),
+            TimeControl::Fischer { increment, .. } => (increment, None),
+            TimeControl::Bronstein { delay, .. } => (delay, None),
+            TimeControl::MovesPerPeriod { moves, .. } => {
+                let made = self.moves[Self::index(white)] % moves;
+                (Duration::ZERO, Some((moves - made) as u64))
+            }
+        };
+
+        allocate(self.remaining(white), bonus, moves_to_go)
+    }
+}
+
+/// Shares out the time left over the moves still to play, plus most of what is added each move
+/// * `moves_to_go` - moves until more time is added, if known
+/// * always leaves a little time for overheads
+pub fn allocate(remaining: Duration, increment: Duration, moves_to_go: Option<u64>) -> Duration {
+    let moves_to_go = moves_to_go.unwrap_or(DEFAULT_MOVES_TO_GO).max(1) as u32;
+    let share = remaining / moves_to_go + increment * 3 / 4;
+    let safe = remaining.saturating_sub(MOVE_OVERHEAD);
+    share.min(safe).max(Duration::from_millis(1))
+}
+
+/// Writes a time as `h:mm:ss` or `m:ss`, with tenths of a second once under 10 seconds
+pub fn format(time: Duration) -> String {
+    let seconds = time.as_secs();
+    if seconds >= 3600 {
+        return format!(
+            "{}:{:02}:{:02}",
+            seconds / 3600,
+            seconds / 60 % 60,
+            seconds % 60
+        );
+    }
+    if seconds < 10 {
+        return format!("0:{:02}.{}", seconds, time.subsec_millis() / 100);
+    }
+
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
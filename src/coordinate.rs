@@ -2,7 +2,7 @@ use crate::board::{NUM_COLS, NUM_ROWS};
 use crate::Error;
 
 /// (x, y) coordinate with A1 as (0, 0)
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub struct Coordinate {
     pub x: usize,
     pub y: usize,
@@ -11,7 +11,7 @@ pub struct Coordinate {
 impl Coordinate {
     pub fn new(x: usize, y: usize) -> Result<Coordinate, Error> {
         if x >= NUM_ROWS || y >= NUM_COLS {
-            return Err(Error::IndexOutOfRange);
+            return Err(Error::OutOfBounds { x, y });
         }
 
         Ok(Coordinate { x, y })
@@ -22,17 +22,26 @@ impl Coordinate {
     /// * e.g. A1 => (0, 0)
     /// * e.g. E4 => (4, 3)
     pub fn from_alphanumeric(position: &str) -> Result<Coordinate, Error> {
-        if position.len() != 2 {
-            return Err(Error::InvalidArgument);
-        }
+        let error = || Error::InvalidSquare {
+            value: String::from(position),
+        };
 
         let coordinates: Vec<char> = position.chars().collect();
-        if !coordinates[0].is_lowercase() || !coordinates[1].is_numeric() {
-            return Err(Error::InvalidArgument);
+        if coordinates.len() != 2
+            || !('a'..='h').contains(&coordinates[0])
+            || !('1'..='8').contains(&coordinates[1])
+        {
+            return Err(error());
         }
 
         let x = coordinates[0] as usize - 97; // a is 97
         let y = coordinates[1] as usize - 49; // 0 is 48, minus 49 to be 0-indexed
         Coordinate::new(x, y)
     }
+
+    /// Convert 0-indexed coordinates to an alphanumeric grid coordinate
+    /// * e.g. (4, 3) => e4
+    pub fn to_alphanumeric(&self) -> String {
+        format!("{}{}", (self.x as u8 + b'a') as char, self.y + 1)
+    }
 }
@@ -0,0 +1,897 @@
+use crate::attacks;
+use crate::bitboard::{self, Bitboards};
+use crate::board::{Board, NUM_COLS, NUM_ROWS};
+use crate::pieces::Id;
+use crate::Error;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+/// Most pieces, kings included, that tables can be generated for
+pub const MAX_PIECES: usize = 4;
+
+// start of every table file, with the format version
+const MAGIC: [u8; 4] = *b"DTM1";
+
+// bytes stored for each position, besides distances
+const DRAW: u8 = 0;
+const ILLEGAL: u8 = u8::MAX;
+
+// pieces in the order they are written in material names, e.g. `KRPvKR`
+const NAME_ORDER: [Id; 6] = [
+    Id::King,
+    Id::Queen,
+    Id::Rook,
+    Id::Bishop,
+    Id::Knight,
+    Id::Pawn,
+];
+const PROMOTIONS: [Id; 4] = [Id::Queen, Id::Rook, Id::Bishop, Id::Knight];
+
+// without pawns the board can be turned so the white king is in the a1-d1-d4 triangle
+const TRIANGLE: [Option<usize>; 16] = [
+    Some(0),
+    Some(1),
+    Some(2),
+    Some(3),
+    None,
+    Some(4),
+    Some(5),
+    Some(6),
+    None,
+    None,
+    Some(7),
+    Some(8),
+    None,
+    None,
+    None,
+    Some(9),
+];
+
+/// Distance to mate with perfect play, in plies, for the side to move
+/// * `Win` - the side to move mates in this many plies
+/// * `Loss` - the side to move is mated in this many plies, 0 if it already is
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Dtm {
+    Win(usize),
+    Loss(usize),
+    Draw,
+}
+
+impl Dtm {
+    /// Result for the side that made the move leading to this position
+    pub fn before(&self) -> Dtm {
+        match self {
+            Dtm::Win(plies) => Dtm::Loss(plies + 1),
+            Dtm::Loss(plies) => Dtm::Win(plies + 1),
+            Dtm::Draw => Dtm::Draw,
+        }
+    }
+
+    /// Checks if the side to move would rather have this result, which means quicker wins and
+    /// slower losses
+    pub fn better(&self, other: &Dtm) -> bool {
+        self.rank() > other.rank()
+    }
+
+    fn rank(&self) -> i32 {
+        match self {
+            Dtm::Win(plies) => i32::MAX - *plies as i32,
+            Dtm::Draw => 0,
+            Dtm::Loss(plies) => i32::MIN + 1 + *plies as i32,
+        }
+    }
+
+    /// Odd distances are wins and even ones are losses, so a byte holds both
+    fn from_byte(byte: u8) -> Option<Dtm> {
+        match byte {
+            DRAW => Some(Dtm::Draw),
+            ILLEGAL => None,
+            byte => {
+                let plies = (byte - 1) as usize;
+                Some(match plies % 2 {
+                    1 => Dtm::Win(plies),
+                    _ => Dtm::Loss(plies),
+                })
+            }
+        }
+    }
+
+    fn to_byte(self) -> u8 {
+        match self {
+            Dtm::Win(plies) | Dtm::Loss(plies) => (plies + 1).min(ILLEGAL as usize - 1) as u8,
+            Dtm::Draw => DRAW,
+        }
+    }
+}
+
+/// A piece and the square it is on, with A1 as 0
+type Placed = (Id, bool, usize);
+
+/// Which pieces are where in a table's index
+/// * `pieces` - white king, black king, then white's other pieces and black's, in [NAME_ORDER]
+/// * `pawns` - boards with pawns can only be mirrored from side to side
+#[derive(Clone)]
+struct Layout {
+    name: String,
+    pieces: Vec<(Id, bool)>,
+    pawns: bool,
+}
+
+impl Layout {
+    /// Reads material written like `KQvK`, with white's pieces first
+    fn parse(name: &str) -> Result<Layout, Error> {
+        let error = || Error::Tablebase {
+            message: format!("{} is not a valid ending", name),
+        };
+
+        let (white, black) = name.split_once('v').ok_or_else(error)?;
+        let mut pieces: Vec<(Id, bool)> = Vec::new();
+        for (side, letters) in [(true, white), (false, black)] {
+            let mut ids: Vec<Id> = Vec::new();
+            for letter in letters.chars() {
+                ids.push(Id::from_char(letter).map_err(|_| error())?);
+            }
+            if ids.iter().filter(|id| **id == Id::King).count() != 1 {
+                return Err(error());
+            }
+            pieces.extend(ids.into_iter().map(|id| (id, side)));
+        }
+
+        if pieces.len() > MAX_PIECES {
+            return Err(Error::Tablebase {
+                message: format!(
+                    "only endings with up to {} pieces are supported",
+                    MAX_PIECES
+                ),
+            });
+        }
+
+        Ok(Self::from_pieces(&pieces))
+    }
+
+    fn from_pieces(pieces: &[(Id, bool)]) -> Layout {
+        let mut sorted: Vec<(Id, bool)> = Vec::new();
+        let kings = [(Id::King, true), (Id::King, false)];
+        sorted.extend(kings.iter().cloned());
+        for white in [true, false] {
+            for id in &NAME_ORDER[1..] {
+                let count = pieces
+                    .iter()
+                    .filter(|(other, side)| other == id && *side == white)
+                    .count();
+                sorted.extend(std::iter::repeat_n((id.clone(), white), count));
+            }
+        }
+
+        let pawns = sorted.iter().any(|(id, _)| *id == Id::Pawn);
+        Layout {
+            name: material(&sorted),
+            pieces: sorted,
+            pawns,
+        }
+    }
+
+    /// Squares the white king can be on once the board is turned
+    fn king_squares(&self) -> usize {
+        if self.pawns {
+            32
+        } else {
+            10
+        }
+    }
+
+    fn size(&self) -> usize {
+        2 * self.king_squares() * 64usize.pow(self.pieces.len() as u32 - 1)
+    }
+
+    /// Index of a position, after turning the board to where the tables store it
+    fn index(&self, squares: &[usize], white: bool) -> usize {
+        let squares = self.canonical(squares);
+        let king = squares[0];
+        let (x, y) = (king % NUM_COLS, king / NUM_COLS);
+        let king = match self.pawns {
+            true => y * 4 + x,
+            false => TRIANGLE[y * 4 + x].unwrap_or(0),
+        };
+
+        let mut index = if white { 0 } else { 1 } * self.king_squares() + king;
+        for square in &squares[1..] {
+            index = index * 64 + square;
+        }
+        index
+    }
+
+    /// Reverses [Layout::index]
+    fn decode(&self, mut index: usize) -> (Vec<usize>, bool) {
+        let mut squares = vec![0; self.pieces.len()];
+        for i in (1..squares.len()).rev() {
+            squares[i] = index % 64;
+            index /= 64;
+        }
+
+        let king = index % self.king_squares();
+        squares[0] = match self.pawns {
+            true => (king / 4) * NUM_COLS + king % 4,
+            false => {
+                let i = TRIANGLE.iter().position(|i| *i == Some(king)).unwrap_or(0);
+                (i / 4) * NUM_COLS + i % 4
+            }
+        };
+        (squares, index / self.king_squares() == 0)
+    }
+
+    /// Mirrors and flips the board so the white king is in the part of the board that is stored
+    fn canonical(&self, squares: &[usize]) -> Vec<usize> {
+        let mut squares = squares.to_vec();
+        let transform = |squares: &mut Vec<usize>, f: &dyn Fn(usize, usize) -> (usize, usize)| {
+            for square in squares.iter_mut() {
+                let (x, y) = f(*square % NUM_COLS, *square / NUM_COLS);
+                *square = y * NUM_COLS + x;
+            }
+        };
+
+        let (x, _) = (squares[0] % NUM_COLS, squares[0] / NUM_COLS);
+        if x >= NUM_COLS / 2 {
+            transform(&mut squares, &|x, y| (NUM_COLS - 1 - x, y));
+        }
+        if self.pawns {
+            return squares;
+        }
+
+        if squares[0] / NUM_COLS >= NUM_ROWS / 2 {
+            transform(&mut squares, &|x, y| (x, NUM_ROWS - 1 - y));
+        }
+        // with the king on the diagonal, the first piece off it decides, so that every position
+        // only has one index
+        let below = squares
+            .iter()
+            .map(|square| (square % NUM_COLS, square / NUM_COLS))
+            .find(|(x, y)| x != y)
+            .is_none_or(|(x, y)| y < x);
+        if !below {
+            transform(&mut squares, &|x, y| (y, x));
+        }
+        squares
+    }
+
+    fn bitboards(&self, squares: &[usize]) -> Bitboards {
+        let mut bitboards = Bitboards::new();
+        for ((id, white), square) in self.pieces.iter().zip(squares) {
+            bitboards.set(*square, id, *white);
+        }
+        bitboards
+    }
+
+    /// Checks that pieces don't share squares, pawns aren't on the back ranks and the side that
+    /// just moved isn't in check
+    fn valid(&self, squares: &[usize], white: bool) -> bool {
+        for (i, square) in squares.iter().enumerate() {
+            if squares[..i].contains(square) {
+                return false;
+            }
+
+            let rank = square / NUM_COLS;
+            if self.pieces[i].0 == Id::Pawn && (rank == 0 || rank == NUM_ROWS - 1) {
+                return false;
+            }
+        }
+
+        !self.bitboards(squares).in_check(!white)
+    }
+}
+
+/// Where a legal move leads
+enum Successor {
+    Inside(usize),
+    Outside(Dtm),
+}
+
+/// Distance to mate for every position with one set of pieces, found by retrograde analysis
+/// * `values` - one byte per position in the order of the layout's index
+pub struct Endgame {
+    layout: Layout,
+    values: Vec<u8>,
+}
+
+impl Endgame {
+    pub fn name(&self) -> &str {
+        &self.layout.name
+    }
+
+    /// Longest forced mate in the table, as a win for the side to move
+    pub fn longest(&self) -> Option<Dtm> {
+        self.values
+            .iter()
+            .filter_map(|byte| Dtm::from_byte(*byte))
+            .filter(|dtm| matches!(dtm, Dtm::Win(_)))
+            .min_by(|a, b| a.rank().cmp(&b.rank()))
+    }
+
+    /// Looks up a position given by its pieces, in any order
+    fn lookup(&self, pieces: &[Placed], white: bool) -> Option<Dtm> {
+        let mut used = vec![false; pieces.len()];
+        let mut squares: Vec<usize> = Vec::new();
+        for (id, side) in &self.layout.pieces {
+            let i = (0..pieces.len())
+                .find(|i| !used[*i] && pieces[*i].0 == *id && pieces[*i].1 == *side)?;
+            used[i] = true;
+            squares.push(pieces[i].2);
+        }
+
+        Dtm::from_byte(self.values[self.layout.index(&squares, white)])
+    }
+
+    /// Writes the table as the magic bytes, the material name, the number of positions and then
+    /// runs of equal values, each as the value and a variable length count
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = MAGIC.to_vec();
+        bytes.push(self.layout.name.len() as u8);
+        bytes.extend(self.layout.name.bytes());
+        bytes.extend((self.values.len() as u32).to_be_bytes());
+
+        let mut i = 0;
+        while i < self.values.len() {
+            let value = self.values[i];
+            let run = self.values[i..]
+                .iter()
+                .take_while(|other| **other == value)
+                .count();
+            bytes.push(value);
+
+            // 7 bits at a time, with the top bit set while there are more to come
+            let mut length = run;
+            loop {
+                let byte = (length & 0x7f) as u8;
+                length >>= 7;
+                match length {
+                    0 => {
+                        bytes.push(byte);
+                        break;
+                    }
+                    _ => bytes.push(byte | 0x80),
+                }
+            }
+            i += run;
+        }
+
+        bytes
+    }
+
+    /// Reads a table written by [Endgame::to_bytes]
+    pub fn from_bytes(bytes: &[u8]) -> Result<Endgame, Error> {
+        let error = |message: &str| Error::Tablebase {
+            message: String::from(message),
+        };
+
+        if bytes.len() < MAGIC.len() + 1 || bytes[..MAGIC.len()] != MAGIC {
+            return Err(error("not an endgame table"));
+        }
+        let length = bytes[MAGIC.len()] as usize;
+        let start = MAGIC.len() + 1;
+        let name = match bytes.get(start..start + length) {
+            Some(name) => String::from_utf8_lossy(name).to_string(),
+            None => return Err(error("table is cut short")),
+        };
+        let layout = Layout::parse(&name)?;
+
+        let mut i = start + length;
+        let size = match bytes.get(i..i + 4) {
+            Some(size) => u32::from_be_bytes(size.try_into().unwrap()) as usize,
+            None => return Err(error("table is cut short")),
+        };
+        if size != layout.size() {
+            return Err(error("table has the wrong number of positions"));
+        }
+        i += 4;
+
+        let mut values: Vec<u8> = Vec::with_capacity(size);
+        while i < bytes.len() {
+            let value = bytes[i];
+            i += 1;
+
+            let mut run = 0;
+            let mut shift = 0;
+            loop {
+                let byte = match bytes.get(i) {
+                    Some(byte) => *byte,
+                    None => return Err(error("table is cut short")),
+                };
+                i += 1;
+                run |= ((byte & 0x7f) as usize) << shift;
+                shift += 7;
+                if byte & 0x80 == 0 {
+                    break;
+                }
+            }
+
+            if values.len() + run > size {
+                return Err(error("table has the wrong number of positions"));
+            }
+            values.extend(std::iter::repeat_n(value, run));
+        }
+
+        if values.len() != size {
+            return Err(error("table has the wrong number of positions"));
+        }
+        Ok(Endgame { layout, values })
+    }
+}
+
+/// Endgame tables, generated or read from files, which can look up positions with any of their
+/// pieces
+/// * `tables` - indexed by material name, e.g. `KQvK`
+pub struct Endgames {
+    tables: HashMap<String, Endgame>,
+}
+
+impl Default for Endgames {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Endgames {
+    pub fn new() -> Endgames {
+        Endgames {
+            tables: HashMap::new(),
+        }
+    }
+
+    /// Names of the tables, sorted
+    pub fn names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.tables.keys().cloned().collect();
+        names.sort();
+        names
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Endgame> {
+        self.tables.get(name)
+    }
+
+    pub fn insert(&mut self, endgame: Endgame) {
+        self.tables.insert(endgame.layout.name.clone(), endgame);
+    }
+
+    /// Reads every `.dtm` file in a directory
+    pub fn open(directory: &Path) -> Result<Endgames, Error> {
+        let error = |error: std::io::Error| Error::Tablebase {
+            message: error.to_string(),
+        };
+
+        let mut endgames = Endgames::new();
+        for entry in fs::read_dir(directory).map_err(error)?.flatten() {
+            let path = entry.path();
+            if path.extension().is_some_and(|extension| extension == "dtm") {
+                let bytes = fs::read(&path).map_err(error)?;
+                endgames.insert(Endgame::from_bytes(&bytes)?);
+            }
+        }
+        Ok(endgames)
+    }
+
+    /// Writes each table to `<material>.dtm` in a directory
+    pub fn save(&self, directory: &Path) -> Result<(), Error> {
+        for endgame in self.tables.values() {
+            let path = directory.join(format!("{}.dtm", endgame.layout.name));
+            fs::write(path, endgame.to_bytes()).map_err(|error| Error::Tablebase {
+                message: error.to_string(),
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Looks up the distance to mate for the side to move
+    /// * returns `None` without a table for the pieces, if either side can still castle, or if a
+    ///   pawn can capture en passant, since the tables have neither
+    pub fn probe(&self, board: &Board) -> Option<Dtm> {
+        if !board.state.castling.is_empty() {
+            return None;
+        }
+
+        if let Some(target) = board.state.en_passant {
+            let white = board.state.white;
+            let square = bitboard::square(target.x, target.y);
+            if attacks::pawn(!white, square) & board.bitboards.get(&Id::Pawn, white) != 0 {
+                return None;
+            }
+        }
+
+        let occupied = board.bitboards.occupied();
+        if occupied.count_ones() as usize > MAX_PIECES {
+            return None;
+        }
+
+        let pieces: Vec<Placed> = bitboard::squares(occupied)
+            .filter_map(|square| {
+                let (id, white) = board.bitboards.piece_at(square)?;
+                Some((id, white, square))
+            })
+            .collect();
+        self.lookup(&pieces, board.state.white)
+    }
+
+    /// Looks up pieces in whichever table has them, with colours swapped if needed
+    /// * endings without mating material are always drawn, so they have no tables
+    fn lookup(&self, pieces: &[Placed], white: bool) -> Option<Dtm> {
+        if insufficient(pieces) {
+            return Some(Dtm::Draw);
+        }
+
+        let names: Vec<(Id, bool)> = pieces
+            .iter()
+            .map(|(id, side, _)| (id.clone(), *side))
+            .collect();
+        if let Some(endgame) = self.tables.get(&material(&names)) {
+            return endgame.lookup(pieces, white);
+        }
+
+        // black's pieces on white's side of the board
+        let flipped: Vec<Placed> = pieces
+            .iter()
+            .map(|(id, side, square)| (id.clone(), !side, square ^ 0b111000))
+            .collect();
+        let names: Vec<(Id, bool)> = flipped
+            .iter()
+            .map(|(id, side, _)| (id.clone(), *side))
+            .collect();
+        self.tables
+            .get(&material(&names))
+            .and_then(|endgame| endgame.lookup(&flipped, !white))
+    }
+
+    /// Makes the table for an ending and any it can turn into through captures and promotions
+    /// * `material` - pieces written like `KQvK`, with white's first
+    /// * tables are always made with the stronger side as white, e.g. `KvKQ` makes `KQvK`
+    pub fn generate(&mut self, material: &str) -> Result<(), Error> {
+        let layout = Layout::from_pieces(&stronger(Layout::parse(material)?.pieces));
+        if self.tables.contains_key(&layout.name) {
+            return Ok(());
+        }
+
+        // every ending this one can turn into has to be known first
+        for (i, (id, white)) in layout.pieces.iter().enumerate() {
+            if *id == Id::King {
+                continue;
+            }
+
+            let mut endings: Vec<Vec<(Id, bool)>> = Vec::new();
+            let mut captured = layout.pieces.clone();
+            captured.remove(i);
+            endings.push(captured);
+            if *id == Id::Pawn {
+                for promotion in &PROMOTIONS {
+                    let mut promoted = layout.pieces.clone();
+                    promoted[i] = (promotion.clone(), *white);
+                    endings.push(promoted);
+                }
+            }
+
+            for pieces in endings {
+                let placed: Vec<Placed> = pieces
+                    .iter()
+                    .map(|(id, side)| (id.clone(), *side, 0))
+                    .collect();
+                if insufficient(&placed) || self.has(&pieces) {
+                    continue;
+                }
+                self.generate(&Layout::from_pieces(&stronger(pieces)).name)?;
+            }
+        }
+
+        let endgame = self.retrograde(layout);
+        self.insert(endgame);
+        Ok(())
+    }
+
+    /// Checks if there is a table for some pieces, with either colours
+    fn has(&self, pieces: &[(Id, bool)]) -> bool {
+        let flipped: Vec<(Id, bool)> = pieces
+            .iter()
+            .map(|(id, white)| (id.clone(), !white))
+            .collect();
+        self.tables.contains_key(&material(pieces)) || self.tables.contains_key(&material(&flipped))
+    }
+
+    /// Works backwards from checkmates, one ply at a time
+    /// * a position is won in n plies once a move reaches a position lost in n - 1
+    /// * a position is lost in n plies once every move reaches a position won in at most n - 1,
+    ///   which is counted down for each position as its moves are found to be winning
+    /// * captures and promotions leave the table, so they are looked up in the smaller tables
+    fn retrograde(&self, layout: Layout) -> Endgame {
+        let size = layout.size();
+        let mut values = vec![DRAW; size];
+        let mut remaining = vec![0u8; size];
+        let mut exits: Vec<Option<Dtm>> = vec![None; size];
+        let mut done = vec![false; size];
+
+        // positions to settle, by distance to mate
+        let mut buckets: Vec<Vec<u32>> = Vec::new();
+        let schedule = |buckets: &mut Vec<Vec<u32>>, index: usize, plies: usize| {
+            if buckets.len() <= plies {
+                buckets.resize(plies + 1, Vec::new());
+            }
+            buckets[plies].push(index as u32);
+        };
+
+        for index in 0..size {
+            let (squares, white) = layout.decode(index);
+            if !layout.valid(&squares, white) || layout.index(&squares, white) != index {
+                values[index] = ILLEGAL;
+                continue;
+            }
+
+            let mut inside: Vec<usize> = Vec::new();
+            let mut best: Option<Dtm> = None;
+            for successor in self.successors(&layout, &squares, white) {
+                match successor {
+                    Successor::Inside(child) => inside.push(child),
+                    Successor::Outside(dtm) => {
+                        let dtm = dtm.before();
+                        if best.is_none_or(|best| dtm.better(&best)) {
+                            best = Some(dtm);
+                        }
+                    }
+                }
+            }
+            inside.sort();
+            inside.dedup();
+            remaining[index] = inside.len() as u8;
+            exits[index] = best;
+
+            match best {
+                Some(Dtm::Win(plies)) => schedule(&mut buckets, index, plies),
+                Some(Dtm::Loss(plies)) if inside.is_empty() => schedule(&mut buckets, index, plies),
+                None if inside.is_empty() && layout.bitboards(&squares).in_check(white) => {
+                    schedule(&mut buckets, index, 0)
+                }
+                _ => (),
+            }
+        }
+
+        let mut plies = 0;
+        while plies < buckets.len() {
+            let bucket = std::mem::take(&mut buckets[plies]);
+            for index in bucket {
+                let index = index as usize;
+                if done[index] {
+                    continue;
+                }
+
+                let dtm = match plies % 2 {
+                    1 => Dtm::Win(plies),
+                    _ => Dtm::Loss(plies),
+                };
+                if values[index] != DRAW && values[index] != dtm.to_byte() {
+                    continue;
+                }
+                values[index] = dtm.to_byte();
+                done[index] = true;
+
+                for parent in self.predecessors(&layout, index) {
+                    if values[parent] != DRAW || done[parent] {
+                        continue;
+                    }
+
+                    match dtm {
+                        // the parent can move here and win
+                        Dtm::Loss(_) => {
+                            values[parent] = Dtm::Win(plies + 1).to_byte();
+                            schedule(&mut buckets, parent, plies + 1);
+                        }
+                        // one less way out for the parent
+                        _ => {
+                            if remaining[parent] == 0 {
+                                continue;
+                            }
+                            remaining[parent] -= 1;
+                            if remaining[parent] > 0 {
+                                continue;
+                            }
+
+                            match exits[parent] {
+                                None => schedule(&mut buckets, parent, plies + 1),
+                                Some(Dtm::Loss(exit)) => {
+                                    schedule(&mut buckets, parent, exit.max(plies + 1))
+                                }
+                                _ => (),
+                            }
+                        }
+                    }
+                }
+            }
+            plies += 1;
+        }
+
+        Endgame { layout, values }
+    }
+
+    /// Legal moves from a position, split into those that stay in the table and those that leave
+    fn successors(&self, layout: &Layout, squares: &[usize], white: bool) -> Vec<Successor> {
+        let bitboards = layout.bitboards(squares);
+        let own = bitboards.colour(white);
+        let enemy = bitboards.colour(!white);
+        let occupied = own | enemy;
+
+        let mut successors: Vec<Successor> = Vec::new();
+        for (i, (id, side)) in layout.pieces.iter().enumerate() {
+            if *side != white {
+                continue;
+            }
+
+            let from = squares[i];
+            let targets = match id {
+                Id::Pawn => {
+                    bitboard::pawn_pushes(white, from, occupied)
+                        | (attacks::pawn(white, from) & enemy)
+                }
+                _ => attacks::piece(id, white, from, occupied) & !own,
+            };
+
+            for target in bitboard::squares(targets) {
+                let captured = squares.iter().position(|square| *square == target);
+                let mut moved = squares.to_vec();
+                moved[i] = target;
+
+                // the mover's king can't be left in check
+                let mut after = bitboards;
+                after.clear(from);
+                after.set(target, id, white);
+                if after.in_check(white) {
+                    continue;
+                }
+
+                let rank = target / NUM_COLS;
+                let promotes = *id == Id::Pawn && (rank == 0 || rank == NUM_ROWS - 1);
+                if captured.is_none() && !promotes {
+                    successors.push(Successor::Inside(layout.index(&moved, !white)));
+                    continue;
+                }
+
+                let mut pieces: Vec<Placed> = layout
+                    .pieces
+                    .iter()
+                    .zip(&moved)
+                    .enumerate()
+                    .filter(|(j, _)| Some(*j) != captured)
+                    .map(|(_, ((id, side), square))| (id.clone(), *side, *square))
+                    .collect();
+                let promotions: Vec<Id> = match promotes {
+                    true => PROMOTIONS.to_vec(),
+                    false => vec![id.clone()],
+                };
+                let slot = pieces.iter().position(|(_, _, square)| *square == target);
+                for promotion in promotions {
+                    if let Some(slot) = slot {
+                        pieces[slot].0 = promotion;
+                    }
+
+                    // tables made by [Endgames::generate] always have what they lead to
+                    let dtm = self.lookup(&pieces, !white).unwrap_or(Dtm::Draw);
+                    successors.push(Successor::Outside(dtm));
+                }
+            }
+        }
+
+        successors
+    }
+
+    /// Positions in the table that can reach this one with a move, which can't be a capture or a
+    /// promotion since those leave the table
+    fn predecessors(&self, layout: &Layout, index: usize) -> Vec<usize> {
+        let (squares, white) = layout.decode(index);
+        let bitboards = layout.bitboards(&squares);
+        let occupied = bitboards.occupied();
+
+        // the side that just moved
+        let mover = !white;
+        let mut parents: Vec<usize> = Vec::new();
+        for (i, (id, side)) in layout.pieces.iter().enumerate() {
+            if *side != mover {
+                continue;
+            }
+
+            let square = squares[i];
+            let origins = match id {
+                Id::Pawn => pawn_origins(mover, square, occupied),
+                _ => attacks::piece(id, mover, square, occupied) & !occupied,
+            };
+            for origin in bitboard::squares(origins) {
+                let mut parent = squares.clone();
+                parent[i] = origin;
+                if layout.valid(&parent, mover) {
+                    parents.push(layout.index(&parent, mover));
+                }
+            }
+        }
+
+        parents.sort();
+        parents.dedup();
+        parents
+    }
+}
+
+/// Squares a pawn could have moved straight ahead from
+fn pawn_origins(white: bool, square: usize, occupied: u64) -> u64 {
+    let back = |square: usize| match white {
+        true => square.checked_sub(NUM_COLS),
+        false => Some(square + NUM_COLS).filter(|square| *square < NUM_COLS * NUM_ROWS),
+    };
+    let starting_rank = if white { 1 } else { NUM_ROWS - 2 };
+
+    let mut origins = 0;
+    let one = match back(square) {
+        Some(one) if occupied & 1 << one == 0 => one,
+        _ => return 0,
+    };
+    if one / NUM_COLS != 0 && one / NUM_COLS != NUM_ROWS - 1 {
+        origins |= 1 << one;
+    }
+
+    // 2 squares from the starting rank
+    if let Some(two) = back(one) {
+        if two / NUM_COLS == starting_rank
+            && one / NUM_COLS != starting_rank
+            && occupied & 1 << two == 0
+        {
+            origins |= 1 << two;
+        }
+    }
+    origins
+}
+
+/// Swaps colours if black has more material, since tables are named with the stronger side first
+fn stronger(pieces: Vec<(Id, bool)>) -> Vec<(Id, bool)> {
+    let value = |white: bool| -> i32 {
+        pieces
+            .iter()
+            .filter(|(_, side)| *side == white)
+            .map(|(id, _)| id.value())
+            .sum()
+    };
+
+    if value(true) >= value(false) {
+        return pieces;
+    }
+    pieces.into_iter().map(|(id, white)| (id, !white)).collect()
+}
+
+/// Checks if neither side could ever mate, which is only a king with at most one minor piece
+fn insufficient(pieces: &[Placed]) -> bool {
+    let others: Vec<&Placed> = pieces.iter().filter(|(id, _, _)| *id != Id::King).collect();
+    match others.as_slice() {
+        [] => true,
+        [(id, _, _)] => *id == Id::Bishop || *id == Id::Knight,
+        _ => false,
+    }
+}
+
+/// Names the pieces on the board like table files, e.g. `KRPvKR`
+/// * white's pieces are written first
+pub fn name(board: &Board) -> String {
+    let mut pieces: Vec<(Id, bool)> = Vec::new();
+    for white in [true, false] {
+        for id in &NAME_ORDER {
+            let count = board.bitboards.get(id, white).count_ones() as usize;
+            pieces.extend(std::iter::repeat_n((id.clone(), white), count));
+        }
+    }
+    material(&pieces)
+}
+
+/// Names a set of pieces, e.g. `KRPvKR`, with white's pieces first
+fn material(pieces: &[(Id, bool)]) -> String {
+    let side = |white: bool| -> String {
+        let mut letters = String::new();
+        for id in &NAME_ORDER {
+            let count = pieces
+                .iter()
+                .filter(|(other, side)| other == id && *side == white)
+                .count();
+            letters.extend(std::iter::repeat_n(id.to_char().unwrap_or('P'), count));
+        }
+        letters
+    };
+
+    format!("{}v{}", side(true), side(false))
+}
@@ -0,0 +1,331 @@
+use crate::coordinate::Coordinate;
+use crate::pieces::Id;
+use std::fmt;
+
+/// Everything that can go wrong in the library
+/// * `Notation` - text that can't be read as a move, with the part that went wrong and where it
+///   starts in the move, after capture and check marks are taken out
+/// * `Move` - a move that can be read but not played, see [MoveError]
+/// * the FEN variants keep the field that couldn't be read
+/// * `InvalidBackRank` - starting pieces that break the rules of a variant, as FEN letters
+/// * `IllegalMove` - a move in a PGN game that couldn't be played, counting plies from 1
+#[derive(Clone, Debug, PartialEq)]
+pub enum Error {
+    Notation {
+        input: String,
+        token: String,
+        position: usize,
+    },
+    UnknownPiece {
+        letter: char,
+    },
+    InvalidSquare {
+        value: String,
+    },
+    OutOfBounds {
+        x: usize,
+        y: usize,
+    },
+    Move(MoveError),
+    InvalidPlacement {
+        value: String,
+    },
+    InvalidSideToMove {
+        value: String,
+    },
+    InvalidCastlingRights {
+        value: String,
+    },
+    InvalidEnPassant {
+        value: String,
+    },
+    InvalidHalfmoveClock {
+        value: String,
+    },
+    InvalidFullmoveNumber {
+        value: String,
+    },
+    InvalidBackRank {
+        value: String,
+        message: String,
+    },
+    InvalidPgn {
+        message: String,
+    },
+    InvalidBook {
+        message: String,
+    },
+    Tablebase {
+        message: String,
+    },
+    IllegalMove {
+        ply: usize,
+        input: String,
+        message: String,
+    },
+}
+
+/// Why a move that was read correctly can't be played
+/// * `Unreachable` - no piece of the type can move to the target, with the first piece found
+///   that would have but for the square `blocked` in its way
+/// * `Ambiguous` - more than one piece can, on the `candidates` squares
+/// * `Pinned` - the piece on `from` would uncover an attack on its king from `by`
+/// * `KingInCheck` - the king would be left in check or moved into it by the `checkers`
+#[derive(Clone, Debug, PartialEq)]
+pub enum MoveError {
+    Unreachable {
+        id: Id,
+        target: Coordinate,
+        blocked: Option<(Coordinate, Coordinate)>,
+    },
+    Ambiguous {
+        id: Id,
+        target: Coordinate,
+        candidates: Vec<Coordinate>,
+    },
+    Pinned {
+        id: Id,
+        from: Coordinate,
+        king: Coordinate,
+        pinner: Id,
+        by: Coordinate,
+        line: Line,
+    },
+    KingInCheck {
+        checkers: Vec<(Id, Coordinate)>,
+    },
+    CannotCastle {
+        kingside: bool,
+        cause: Castling,
+    },
+    InvalidPromotion,
+    PromotionRequired,
+    NotLegal,
+}
+
+/// Direction a piece is pinned along
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Line {
+    Rank,
+    File,
+    Diagonal,
+}
+
+/// Why castling isn't possible
+/// * `RookMoved` - the rook that could have castled is gone from its square, or has moved
+/// * `RightsLost` - neither piece has moved, but the position was set up without the right
+/// * `Blocked` - a piece is on a square the king or rook has to cross or land on
+/// * `Attacked` - the king would cross or land on a square the opponent attacks
+#[derive(Clone, Debug, PartialEq)]
+pub enum Castling {
+    KingMoved,
+    RookMoved,
+    RightsLost,
+    InCheck,
+    Blocked { square: Coordinate },
+    Attacked { square: Coordinate },
+}
+
+impl Error {
+    /// Explanation shown after a rejected move, e.g. `Nf3 is ambiguous, ...`
+    pub fn reason(&self) -> String {
+        match self {
+            Error::Move(error) => error.reason(),
+            Error::Notation { .. } | Error::UnknownPiece { .. } | Error::InvalidSquare { .. } => {
+                format!("is not valid notation, {}", self)
+            }
+            Error::InvalidPgn { message }
+            | Error::InvalidBook { message }
+            | Error::Tablebase { message } => message.clone(),
+            _ => self.to_string(),
+        }
+    }
+}
+
+impl MoveError {
+    /// Explanation that follows the move as it was written
+    pub fn reason(&self) -> String {
+        match self {
+            MoveError::Ambiguous { .. } => format!("is ambiguous, {}", self),
+            MoveError::KingInCheck { checkers } if !checkers.is_empty() => {
+                format!("puts the king in check from {}", pieces(checkers))
+            }
+            MoveError::KingInCheck { .. } => String::from("puts the king in check"),
+            MoveError::CannotCastle { cause, .. } => format!("is not possible, {}", cause),
+            MoveError::InvalidPromotion => String::from("is not a valid promotion"),
+            MoveError::PromotionRequired => {
+                String::from("is not valid because promotion is forced")
+            }
+            MoveError::NotLegal => String::from("is not a legal move"),
+            _ => format!("is not possible, {}", self),
+        }
+    }
+}
+
+impl From<MoveError> for Error {
+    fn from(error: MoveError) -> Error {
+        Error::Move(error)
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Notation {
+                input,
+                token,
+                position,
+            } => write!(
+                f,
+                "unexpected `{}` at character {} of `{}`",
+                token,
+                position + 1,
+                input
+            ),
+            Error::UnknownPiece { letter } => write!(f, "`{}` is not a piece", letter),
+            Error::InvalidSquare { value } => write!(f, "`{}` is not a square", value),
+            Error::OutOfBounds { x, y } => write!(f, "({}, {}) is off the board", x, y),
+            Error::Move(error) => write!(f, "{}", error),
+            Error::InvalidPlacement { value } => write!(f, "invalid piece placement `{}`", value),
+            Error::InvalidSideToMove { value } => write!(f, "invalid side to move `{}`", value),
+            Error::InvalidCastlingRights { value } => {
+                write!(f, "invalid castling rights `{}`", value)
+            }
+            Error::InvalidEnPassant { value } => write!(f, "invalid en passant square `{}`", value),
+            Error::InvalidHalfmoveClock { value } => {
+                write!(f, "invalid halfmove clock `{}`", value)
+            }
+            Error::InvalidFullmoveNumber { value } => {
+                write!(f, "invalid fullmove number `{}`", value)
+            }
+            Error::InvalidBackRank { value, message } => {
+                write!(f, "invalid back rank `{}`, {}", value, message)
+            }
+            Error::InvalidPgn { message } => write!(f, "invalid PGN: {}", message),
+            Error::InvalidBook { message } | Error::Tablebase { message } => {
+                write!(f, "{}", message)
+            }
+            Error::IllegalMove {
+                ply,
+                input,
+                message,
+            } => write!(f, "{} (ply {}) {}", input, ply, message),
+        }
+    }
+}
+
+impl fmt::Display for MoveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MoveError::Unreachable {
+                id,
+                target,
+                blocked,
+            } => {
+                write!(f, "no {} can reach {}", id.name(), target.to_alphanumeric())?;
+                match blocked {
+                    Some((from, square)) => write!(
+                        f,
+                        ", the {} on {} is blocked on {}",
+                        id.name(),
+                        from.to_alphanumeric(),
+                        square.to_alphanumeric()
+                    ),
+                    None => Ok(()),
+                }
+            }
+            MoveError::Ambiguous {
+                id,
+                target,
+                candidates,
+            } => {
+                let squares: Vec<String> = candidates
+                    .iter()
+                    .map(|square| square.to_alphanumeric())
+                    .collect();
+                write!(
+                    f,
+                    "{}s on {} can {} reach {}",
+                    id.name(),
+                    list(&squares),
+                    if candidates.len() == 2 { "both" } else { "all" },
+                    target.to_alphanumeric()
+                )
+            }
+            MoveError::Pinned {
+                id,
+                from,
+                king,
+                pinner,
+                by,
+                line,
+            } => write!(
+                f,
+                "the {} on {} is pinned to the king on {} by the {} on {} along the {}",
+                id.name(),
+                from.to_alphanumeric(),
+                king.to_alphanumeric(),
+                pinner.name(),
+                by.to_alphanumeric(),
+                match line {
+                    Line::Rank => "rank",
+                    Line::File => "file",
+                    Line::Diagonal => "diagonal",
+                }
+            ),
+            MoveError::KingInCheck { checkers } if !checkers.is_empty() => {
+                write!(f, "{} would give check", pieces(checkers))
+            }
+            MoveError::KingInCheck { .. } => write!(f, "the king would be in check"),
+            MoveError::CannotCastle { kingside, cause } => write!(
+                f,
+                "cannot castle {}, {}",
+                if *kingside { "kingside" } else { "queenside" },
+                cause
+            ),
+            MoveError::InvalidPromotion => write!(f, "only pawns reaching the last rank promote"),
+            MoveError::PromotionRequired => write!(f, "pawns reaching the last rank must promote"),
+            MoveError::NotLegal => write!(f, "not a legal move"),
+        }
+    }
+}
+
+impl fmt::Display for Castling {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Castling::KingMoved => write!(f, "the king has already moved"),
+            Castling::RookMoved => write!(f, "the rook has already moved or been taken"),
+            Castling::RightsLost => write!(f, "the right to castle was given up"),
+            Castling::InCheck => write!(f, "the king is in check"),
+            Castling::Blocked { square } => {
+                write!(f, "{} is in the way", square.to_alphanumeric())
+            }
+            Castling::Attacked { square } => write!(
+                f,
+                "the king would pass through or land on {}, which is attacked",
+                square.to_alphanumeric()
+            ),
+        }
+    }
+}
+
+/// Joins words into a list, e.g. `b1, d2 and f1`
+fn list(words: &[String]) -> String {
+    match words.split_last() {
+        Some((last, rest)) if !rest.is_empty() => format!("{} and {}", rest.join(", "), last),
+        _ => words.join(""),
+    }
+}
+
+/// Describes pieces by type and square, e.g. `the rook on e8 and the bishop on b4`
+fn pieces(pieces: &[(Id, Coordinate)]) -> String {
+    let words: Vec<String> = pieces
+        .iter()
+        .map(|(id, square)| format!("the {} on {}", id.name(), square.to_alphanumeric()))
+        .collect();
+    list(&words)
+}
+
+impl std::error::Error for Error {}
+
+impl std::error::Error for MoveError {}
@@ -0,0 +1,283 @@
+use crate::attacks;
+use crate::bitboard;
+use crate::board::{Board, NUM_COLS, NUM_ROWS};
+use crate::pieces::{Id, Piece};
+
+// game phase when all the pieces are on the board
+const MAX_PHASE: i32 = 24;
+
+// piece-square tables are laid out as seen by white, with the 8th rank first
+#[rustfmt::skip]
+const PAWN_MIDDLEGAME: [i32; 64] = [
+     0,  0,  0,  0,  0,  0,  0,  0,
+    50, 50, 50, 50, 50, 50, 50, 50,
+    10, 10, 20, 30, 30, 20, 10, 10,
+     5,  5, 10, 25, 25, 10,  5,  5,
+     0,  0,  0, 20, 20,  0,  0,  0,
+     5, -5,-10,  0,  0,-10, -5,  5,
+     5, 10, 10,-20,-20, 10, 10,  5,
+     0,  0,  0,  0,  0,  0,  0,  0,
+];
+
+#[rustfmt::skip]
+const PAWN_ENDGAME: [i32; 64] = [
+     0,  0,  0,  0,  0,  0,  0,  0,
+    80, 80, 80, 80, 80, 80, 80, 80,
+    50, 50, 50, 50, 50, 50, 50, 50,
+    30, 30, 30, 30, 30, 30, 30, 30,
+    20, 20, 20, 20, 20, 20, 20, 20,
+    10, 10, 10, 10, 10, 10, 10, 10,
+    10, 10, 10, 10, 10, 10, 10, 10,
+     0,  0,  0,  0,  0,  0,  0,  0,
+];
+
+#[rustfmt::skip]
+const KNIGHT: [i32; 64] = [
+   -50,-40,-30,-30,-30,-30,-40,-50,
+   -40,-20,  0,  0,  0,  0,-20,-40,
+   -30,  0, 10, 15, 15, 10,  0,-30,
+   -30,  5, 15, 20, 20, 15,  5,-30,
+   -30,  0, 15, 20, 20, 15,  0,-30,
+   -30,  5, 10, 15, 15, 10,  5,-30,
+   -40,-20,  0,  5,  5,  0,-20,-40,
+   -50,-40,-30,-30,-30,-30,-40,-50,
+];
+
+#[rustfmt::skip]
+const BISHOP: [i32; 64] = [
+   -20,-10,-10,-10,-10,-10,-10,-20,
+   -10,  0,  0,  0,  0,  0,  0,-10,
+   -10,  0,  5, 10, 10,  5,  0,-10,
+   -10,  5,  5, 10, 10,  5,  5,-10,
+   -10,  0, 10, 10, 10, 10,  0,-10,
+   -10, 10, 10, 10, 10, 10, 10,-10,
+   -10,  5,  0,  0,  0,  0,  5,-10,
+   -20,-10,-10,-10,-10,-10,-10,-20,
+];
+
+#[rustfmt::skip]
+const ROOK: [i32; 64] = [
+     0,  0,  0,  0,  0,  0,  0,  0,
+     5, 10, 10, 10, 10, 10, 10,  5,
+    -5,  0,  0,  0,  0,  0,  0, -5,
+    -5,  0,  0,  0,  0,  0,  0, -5,
+    -5,  0,  0,  0,  0,  0,  0, -5,
+    -5,  0,  0,  0,  0,  0,  0, -5,
+    -5,  0,  0,  0,  0,  0,  0, -5,
+     0,  0,  0,  5,  5,  0,  0,  0,
+];
+
+#[rustfmt::skip]
+const QUEEN: [i32; 64] = [
+   -20,-10,-10, -5, -5,-10,-10,-20,
+   -10,  0,  0,  0,  0,  0,  0,-10,
+   -10,  0,  5,  5,  5,  5,  0,-10,
+    -5,  0,  5,  5,  5,  5,  0, -5,
+     0,  0,  5,  5,  5,  5,  0, -5,
+   -10,  5,  5,  5,  5,  5,  0,-10,
+   -10,  0,  5,  0,  0,  0,  0,-10,
+   -20,-10,-10, -5, -5,-10,-10,-20,
+];
+
+#[rustfmt::skip]
+const KING_MIDDLEGAME: [i32; 64] = [
+   -30,-40,-40,-50,-50,-40,-40,-30,
+   -30,-40,-40,-50,-50,-40,-40,-30,
+   -30,-40,-40,-50,-50,-40,-40,-30,
+   -30,-40,-40,-50,-50,-40,-40,-30,
+   -20,-30,-30,-40,-40,-30,-30,-20,
+   -10,-20,-20,-20,-20,-20,-20,-10,
+    20, 20,  0,  0,  0,  0, 20, 20,
+    20, 30, 10,  0,  0, 10, 30, 20,
+];
+
+#[rustfmt::skip]
+const KING_ENDGAME: [i32; 64] = [
+   -50,-40,-30,-20,-20,-30,-40,-50,
+   -30,-20,-10,  0,  0,-10,-20,-30,
+   -30,-10, 20, 30, 30, 20,-10,-30,
+   -30,-10, 30, 40, 40, 30,-10,-30,
+   -30,-10, 30, 40, 40, 30,-10,-30,
+   -30,-10, 20, 30, 30, 20,-10,-30,
+   -30,-30,  0,  0,  0,  0,-30,-30,
+   -50,-30,-30,-30,-30,-30,-30,-50,
+];
+
+/// Middlegame and endgame pair, blended by how much material is left
+pub type Score = (i32, i32);
+
+/// Every tunable term of the evaluation, in centipawns
+/// * tables indexed by piece type use [Id::index]
+/// * `material` - value of each piece
+/// * `middlegame`, `endgame` - piece-square tables as seen by white, 8th rank first
+/// * `phase` - how much each piece counts towards the game still being in the middlegame
+/// * `doubled_pawn`, `isolated_pawn` - penalties for each pawn with that weakness
+/// * `passed_pawn` - bonus for a passed pawn by its rank, counted from its own side
+/// * `mobility` - bonus for each square a piece can move to
+pub struct Weights {
+    pub material: [Score; 6],
+    pub middlegame: [[i32; 64]; 6],
+    pub endgame: [[i32; 64]; 6],
+    pub phase: [i32; 6],
+    pub doubled_pawn: Score,
+    pub isolated_pawn: Score,
+    pub passed_pawn: [Score; NUM_ROWS],
+    pub mobility: [Score; 6],
+}
+
+impl Default for Weights {
+    fn default() -> Weights {
+        Weights {
+            material: [
+                (100, 120),
+                (320, 300),
+                (330, 320),
+                (500, 550),
+                (900, 950),
+                (0, 0),
+            ],
+            middlegame: [
+                PAWN_MIDDLEGAME,
+                KNIGHT,
+                BISHOP,
+                ROOK,
+                QUEEN,
+                KING_MIDDLEGAME,
+            ],
+            endgame: [PAWN_ENDGAME, KNIGHT, BISHOP, ROOK, QUEEN, KING_ENDGAME],
+            phase: [0, 1, 1, 2, 4, 0],
+            doubled_pawn: (-10, -20),
+            isolated_pawn: (-10, -15),
+            passed_pawn: [
+                (0, 0),
+                (5, 10),
+                (10, 20),
+                (15, 30),
+                (25, 50),
+                (40, 80),
+                (60, 120),
+                (0, 0),
+            ],
+            mobility: [(0, 0), (4, 4), (5, 5), (2, 4), (1, 2), (0, 0)],
+        }
+    }
+}
+
+/// Scores the position in centipawns from the perspective of the side to move
+pub fn evaluate(board: &Board, white: bool) -> i32 {
+    evaluate_with(board, white, &Weights::default())
+}
+
+/// Scores the position using custom weights
+pub fn evaluate_with(board: &Board, white: bool, weights: &Weights) -> i32 {
+    let mut middlegame = 0;
+    let mut endgame = 0;
+    let mut phase = 0;
+
+    for row in &board.grid {
+        for piece in row.iter().flatten() {
+            let index = piece.id.index();
+            let square = square(piece);
+            let mobility = mobility(board, piece);
+
+            let mut score = (
+                weights.material[index].0
+                    + weights.middlegame[index][square]
+                    + weights.mobility[index].0 * mobility,
+                weights.material[index].1
+                    + weights.endgame[index][square]
+                    + weights.mobility[index].1 * mobility,
+            );
+
+            if piece.id == Id::Pawn {
+                let pawn = pawn_structure(board, piece, weights);
+                score.0 += pawn.0;
+                score.1 += pawn.1;
+            }
+
+            let sign = if piece.white == white { 1 } else { -1 };
+            middlegame += sign * score.0;
+            endgame += sign * score.1;
+            phase += weights.phase[index];
+        }
+    }
+
+    // promotions can push the phase past the starting material
+    let phase = phase.min(MAX_PHASE);
+    (middlegame * phase + endgame * (MAX_PHASE - phase)) / MAX_PHASE
+}
+
+/// Index into a piece-square table, flipping the board for black
+fn square(piece: &Piece) -> usize {
+    let rank = match piece.white {
+        true => NUM_ROWS - 1 - piece.position.y,
+        false => piece.position.y,
+    };
+    rank * NUM_COLS + piece.position.x
+}
+
+/// Counts the squares a knight, bishop, rook or queen can move to, ignoring pins
+fn mobility(board: &Board, piece: &Piece) -> i32 {
+    match piece.id {
+        Id::Knight | Id::Bishop | Id::Rook | Id::Queen => (),
+        _ => return 0,
+    }
+
+    let square = bitboard::square(piece.position.x, piece.position.y);
+    let occupied = board.bitboards.occupied();
+    let own = board.bitboards.colour(piece.white);
+    let targets = attacks::piece(&piece.id, piece.white, square, occupied) & !own;
+    targets.count_ones() as i32
+}
+
+/// Doubled, isolated and passed pawn terms for a single pawn
+fn pawn_structure(board: &Board, pawn: &Piece, weights: &Weights) -> Score {
+    let mut score = (0, 0);
+    let x = pawn.position.x;
+    let y = pawn.position.y;
+    let is_pawn = |x: usize, y: usize, white: bool| match &board.grid[y][x] {
+        Some(piece) => piece.id == Id::Pawn && piece.white == white,
+        None => false,
+    };
+
+    // only the pawns behind count as doubled, so a pair is penalised once
+    let behind = match pawn.white {
+        true => 0..y,
+        false => (y + 1)..NUM_ROWS,
+    };
+    if behind.clone().any(|rank| is_pawn(x, rank, pawn.white)) {
+        score.0 += weights.doubled_pawn.0;
+        score.1 += weights.doubled_pawn.1;
+    }
+
+    let files: Vec<usize> = [x.wrapping_sub(1), x + 1]
+        .into_iter()
+        .filter(|file| *file < NUM_COLS)
+        .collect();
+    let supported = files
+        .iter()
+        .any(|file| (0..NUM_ROWS).any(|rank| is_pawn(*file, rank, pawn.white)));
+    if !supported {
+        score.0 += weights.isolated_pawn.0;
+        score.1 += weights.isolated_pawn.1;
+    }
+
+    // passed if no enemy pawn is ahead on the same or an adjacent file
+    let ahead = match pawn.white {
+        true => (y + 1)..NUM_ROWS,
+        false => 0..y,
+    };
+    let blocked = ahead.into_iter().any(|rank| {
+        is_pawn(x, rank, !pawn.white) || files.iter().any(|file| is_pawn(*file, rank, !pawn.white))
+    });
+    if !blocked {
+        let advanced = match pawn.white {
+            true => y,
+            false => NUM_ROWS - 1 - y,
+        };
+        score.0 += weights.passed_pawn[advanced].0;
+        score.1 += weights.passed_pawn[advanced].1;
+    }
+
+    score
+}
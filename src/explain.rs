@@ -0,0 +1,293 @@
+use crate::attacks;
+use crate::bitboard::{self, Bitboards};
+use crate::board::{Board, KINGSIDE_CASTLE, NUM_COLS, NUM_ROWS, QUEENSIDE_CASTLE};
+use crate::coordinate::Coordinate;
+use crate::error::{Castling, Line, MoveError};
+use crate::pieces::moves::MoveType;
+use crate::pieces::{Id, MoveChecker, Piece};
+
+impl Board {
+    /// Works out why a move leaves the king in check
+    /// * a piece is pinned if the king was safe until that piece was lifted off the board
+    /// * otherwise, gives the pieces that would be attacking the king after the move
+    pub(crate) fn exposed(&self, move_type: &MoveType, white: bool) -> MoveError {
+        let mut after = self.bitboards;
+        after.apply(move_type, white);
+        let in_check = MoveError::KingInCheck {
+            checkers: checkers(&after, white),
+        };
+
+        let from = match move_type {
+            MoveType::Normal { piece, .. } if piece.id != Id::King => piece.position,
+            MoveType::EnPassant { from, .. } => *from,
+            _ => return in_check,
+        };
+
+        let king = self.bitboards.get(&Id::King, white);
+        if king == 0 || self.bitboards.in_check(white) {
+            return in_check;
+        }
+        let king = king.trailing_zeros() as usize;
+
+        // only the piece in the way of the pinning piece is lifted
+        let mut lifted = self.bitboards;
+        lifted.clear(bitboard::square(from.x, from.y));
+        let pinner = bitboard::squares(lifted.attackers(king, !white))
+            .find_map(|square| Some((lifted.piece_at(square)?.0, coordinate(square))));
+        let ((pinner, by), id) = match (pinner, &self.grid[from.y][from.x]) {
+            (Some(pinner), Some(piece)) => (pinner, piece.id.clone()),
+            _ => return in_check,
+        };
+
+        let king = coordinate(king);
+        let line = if king.y == from.y {
+            Line::Rank
+        } else if king.x == from.x {
+            Line::File
+        } else {
+            Line::Diagonal
+        };
+        MoveError::Pinned {
+            id,
+            from,
+            king,
+            pinner,
+            by,
+            line,
+        }
+    }
+
+    /// Works out why no piece of a type can move to a square
+    /// * `x` and `y` - the file and rank the piece has to come from, if the move gives them
+    /// * finds the first piece that could have made the move but for a piece in its way, which
+    ///   can be one of its own on the target
+    pub(crate) fn unreachable(
+        &self,
+        id: &Id,
+        target: Coordinate,
+        white: bool,
+        x: Option<usize>,
+        y: Option<usize>,
+    ) -> MoveError {
+        let blocked = bitboard::squares(self.bitboards.get(id, white))
+            .map(coordinate)
+            .filter(|from| x.is_none_or(|x| from.x == x) && y.is_none_or(|y| from.y == y))
+            .find_map(|from| Some((from, self.blocker(id, from, target, white)?)));
+
+        MoveError::Unreachable {
+            id: id.clone(),
+            target,
+            blocked,
+        }
+    }
+
+    /// First square in the way of a piece moving to a target it could reach on an empty board
+    fn blocker(
+        &self,
+        id: &Id,
+        from: Coordinate,
+        target: Coordinate,
+        white: bool,
+    ) -> Option<Coordinate> {
+        let own = |square: &Coordinate| match &self.grid[square.y][square.x] {
+            Some(piece) => piece.white == white,
+            None => false,
+        };
+        let occupied = |square: &Coordinate| self.grid[square.y][square.x].is_some();
+
+        let dx = target.x as i32 - from.x as i32;
+        let dy = target.y as i32 - from.y as i32;
+        let path = match id {
+            // pawns can't take straight ahead, so the target is part of the path
+            Id::Pawn => {
+                let forward = if white { 1 } else { -1 };
+                let start = if white { 1 } else { NUM_ROWS - 2 };
+                let two = dy == 2 * forward && from.y == start;
+                if dx != 0 || (dy != forward && !two) {
+                    return None;
+                }
+
+                let mut path = between(from, target);
+                path.push(target);
+                return path.into_iter().find(occupied);
+            }
+            Id::Knight | Id::King => {
+                let square = bitboard::square(target.x, target.y);
+                if attacks::piece(id, white, bitboard::square(from.x, from.y), 0) & (1 << square)
+                    == 0
+                {
+                    return None;
+                }
+                Vec::new()
+            }
+            _ => {
+                let straight = dx == 0 || dy == 0;
+                let diagonal = dx.abs() == dy.abs();
+                let slides = match id {
+                    Id::Rook => straight,
+                    Id::Bishop => diagonal,
+                    _ => straight || diagonal,
+                };
+                if !slides || (dx == 0 && dy == 0) {
+                    return None;
+                }
+                between(from, target)
+            }
+        };
+
+        match path.into_iter().find(occupied) {
+            Some(square) => Some(square),
+            None if own(&target) => Some(target),
+            None => None,
+        }
+    }
+
+    /// Works out why castling to one side isn't possible
+    /// * without the right, says whether the king or the rook moved, going by how many times the
+    ///   pieces on their home squares have moved
+    /// * otherwise, gives the first square in the way or under attack, nearest the king
+    pub(crate) fn uncastleable(&self, kingside: bool, white: bool) -> MoveError {
+        let cause = self.castling_cause(kingside, white);
+        MoveError::CannotCastle { kingside, cause }
+    }
+
+    fn castling_cause(&self, kingside: bool, white: bool) -> Castling {
+        let rank = if white { 0 } else { NUM_ROWS - 1 };
+        let is = |piece: &Option<Piece>, id: &Id| match piece {
+            Some(piece) => piece.id == *id && piece.white == white,
+            None => false,
+        };
+
+        let king = match self.grid[rank]
+            .iter()
+            .flatten()
+            .find(|piece| piece.id == Id::King && piece.white == white)
+        {
+            Some(king) => king,
+            None => return Castling::KingMoved,
+        };
+        let side = |x: usize| {
+            if kingside {
+                x > king.position.x
+            } else {
+                x < king.position.x
+            }
+        };
+
+        let files: Vec<usize> = self
+            .state
+            .castling_files(white)
+            .into_iter()
+            .filter(|x| side(*x))
+            .collect();
+        if files.is_empty() {
+            if king.moves > 0 {
+                return Castling::KingMoved;
+            }
+
+            // the rook furthest out is the one that would have castled
+            let rooks = (0..NUM_COLS).filter(|x| side(*x) && is(&self.grid[rank][*x], &Id::Rook));
+            let rook = match kingside {
+                true => rooks.max(),
+                false => rooks.min(),
+            };
+            return match rook.and_then(|x| self.grid[rank][x].as_ref()) {
+                Some(rook) if rook.moves == 0 => Castling::RightsLost,
+                _ => Castling::RookMoved,
+            };
+        }
+
+        if MoveChecker::in_check(self, white) {
+            return Castling::InCheck;
+        }
+
+        let rook_x = match files.iter().find(|x| is(&self.grid[rank][**x], &Id::Rook)) {
+            Some(x) => *x,
+            None => return Castling::RookMoved,
+        };
+        let targets = if kingside {
+            &KINGSIDE_CASTLE
+        } else {
+            &QUEENSIDE_CASTLE
+        };
+        let king_x = king.position.x;
+
+        // squares are checked from the king outwards
+        let outwards = |left: usize, right: usize| {
+            let mut files: Vec<usize> = (left..=right).collect();
+            files.sort_by_key(|x| x.abs_diff(king_x));
+            files
+        };
+
+        let files = [king_x, rook_x, targets[0], targets[1]];
+        let (left, right) = (*files.iter().min().unwrap(), *files.iter().max().unwrap());
+        for x in outwards(left, right) {
+            if x != king_x && x != rook_x && self.grid[rank][x].is_some() {
+                return Castling::Blocked {
+                    square: Coordinate { x, y: rank },
+                };
+            }
+        }
+
+        // the rook has left its square by the time the king lands
+        let mut bitboards = self.bitboards;
+        bitboards.clear(bitboard::square(king_x, rank));
+        bitboards.clear(bitboard::square(rook_x, rank));
+        bitboards.set(bitboard::square(targets[1], rank), &Id::Rook, white);
+        for x in outwards(king_x.min(targets[0]), king_x.max(targets[0])) {
+            let mut test = bitboards;
+            test.set(bitboard::square(x, rank), &Id::King, white);
+            if test.in_check(white) {
+                return Castling::Attacked {
+                    square: Coordinate { x, y: rank },
+                };
+            }
+        }
+
+        // a rook shielding the king's destination only stops shielding it after castling
+        Castling::Attacked {
+            square: Coordinate {
+                x: targets[0],
+                y: rank,
+            },
+        }
+    }
+}
+
+/// Pieces attacking the king of one side
+fn checkers(bitboards: &Bitboards, white: bool) -> Vec<(Id, Coordinate)> {
+    let king = bitboards.get(&Id::King, white);
+    if king == 0 {
+        return Vec::new();
+    }
+
+    let attackers = bitboards.attackers(king.trailing_zeros() as usize, !white);
+    bitboard::squares(attackers)
+        .filter_map(|square| Some((bitboards.piece_at(square)?.0, coordinate(square))))
+        .collect()
+}
+
+/// Squares strictly between two squares on the same line
+fn between(from: Coordinate, to: Coordinate) -> Vec<Coordinate> {
+    let step = |a: usize, b: usize| (b as i32 - a as i32).signum();
+    let (dx, dy) = (step(from.x, to.x), step(from.y, to.y));
+
+    let mut squares: Vec<Coordinate> = Vec::new();
+    let (mut x, mut y) = (from.x as i32 + dx, from.y as i32 + dy);
+    while (x, y) != (to.x as i32, to.y as i32) {
+        squares.push(Coordinate {
+            x: x as usize,
+            y: y as usize,
+        });
+        x += dx;
+        y += dy;
+    }
+    squares
+}
+
+fn coordinate(square: usize) -> Coordinate {
+    Coordinate {
+        x: square % NUM_COLS,
+        y: square / NUM_COLS,
+    }
+}
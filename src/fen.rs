@@ -0,0 +1,300 @@
+use crate::board::{Board, NUM_COLS, NUM_ROWS};
+use crate::coordinate::Coordinate;
+use crate::pieces::moves::MoveType;
+use crate::pieces::{Id, Piece};
+use crate::zobrist;
+use crate::Error;
+
+pub const STARTING_FEN: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+
+impl Board {
+    /// Sets up a board from Forsyth-Edwards Notation
+    /// * castling rights can use rook files instead of `KQkq` for chess960, e.g. `HAha`
+    /// * the move counters are optional and default to `0 1`
+    pub fn from_fen(fen: &str) -> Result<Board, Error> {
+        let fields: Vec<&str> = fen.split_whitespace().collect();
+        let field = |i: usize| fields.get(i).copied().unwrap_or("");
+
+        let mut board = Board::empty();
+        Self::parse_placement(&mut board, field(0))?;
+
+        let white = match field(1) {
+            "w" => true,
+            "b" => false,
+            value => {
+                return Err(Error::InvalidSideToMove {
+                    value: String::from(value),
+                })
+            }
+        };
+
+        board.state.white = white;
+        Self::parse_castling_rights(&mut board, field(2))?;
+        Self::parse_en_passant(&mut board, field(3), white)?;
+
+        if let Some(value) = fields.get(4) {
+            board.state.halfmove_clock =
+                value.parse().map_err(|_| Error::InvalidHalfmoveClock {
+                    value: String::from(*value),
+                })?;
+        }
+
+        if let Some(value) = fields.get(5) {
+            board.state.fullmove_number = match value.parse() {
+                Ok(number) if number > 0 => number,
+                _ => {
+                    return Err(Error::InvalidFullmoveNumber {
+                        value: String::from(*value),
+                    })
+                }
+            };
+        }
+
+        // the state was set directly, so the key has to be worked out again
+        board.hash = zobrist::hash(&board);
+        Ok(board)
+    }
+
+    /// Places the pieces described by the first field, starting from the 8th rank
+    fn parse_placement(board: &mut Board, value: &str) -> Result<(), Error> {
+        let error = || Error::InvalidPlacement {
+            value: String::from(value),
+        };
+
+        let ranks: Vec<&str> = value.split('/').collect();
+        if ranks.len() != NUM_ROWS {
+            return Err(error());
+        }
+
+        for (i, rank) in ranks.iter().enumerate() {
+            let y = NUM_ROWS - i - 1;
+            let mut x = 0;
+            for letter in rank.chars() {
+                match letter.to_digit(10) {
+                    Some(empty) if (1..=NUM_COLS as u32).contains(&empty) => {
+                        x += empty as usize;
+                        continue;
+                    }
+                    Some(_) => return Err(error()),
+                    None => (),
+                }
+
+                if !letter.is_ascii_alphabetic() || x >= NUM_COLS {
+                    return Err(error());
+                }
+
+                let id = Id::from_char(letter.to_ascii_uppercase()).map_err(|_| error())?;
+                let white = letter.is_ascii_uppercase();
+                board.place_piece(x, y, id.to_icon(), white, 0);
+                x += 1;
+            }
+
+            if x != NUM_COLS {
+                return Err(error());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Gives castling rights to the rooks named by the third field
+    fn parse_castling_rights(board: &mut Board, value: &str) -> Result<(), Error> {
+        let error = || Error::InvalidCastlingRights {
+            value: String::from(value),
+        };
+
+        if value == "-" {
+            return Ok(());
+        }
+
+        if value.is_empty() {
+            return Err(error());
+        }
+
+        for letter in value.chars() {
+            if !letter.is_ascii_alphabetic() {
+                return Err(error());
+            }
+
+            let white = letter.is_ascii_uppercase();
+            let rank = if white { 0 } else { NUM_ROWS - 1 };
+            let king_x = match Self::find_king(board, rank, white) {
+                Some(x) => x,
+                None => return Err(error()),
+            };
+
+            // K and Q refer to the outermost rook on that side
+            let rook_x = match letter.to_ascii_uppercase() {
+                'K' => (king_x + 1..NUM_COLS)
+                    .rev()
+                    .find(|x| Self::is_rook(board, *x, rank, white)),
+                'Q' => (0..king_x).find(|x| Self::is_rook(board, *x, rank, white)),
+                file @ 'A'..='H' => {
+                    let x = file as usize - 'A' as usize;
+                    match x != king_x && Self::is_rook(board, x, rank, white) {
+                        true => Some(x),
+                        false => None,
+                    }
+                }
+                _ => None,
+            };
+
+            match rook_x {
+                Some(x) => board.state.castling.push(Coordinate { x, y: rank }),
+                None => return Err(error()),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Records the square a pawn just skipped over so it can be captured en passant
+    fn parse_en_passant(board: &mut Board, value: &str, white: bool) -> Result<(), Error> {
+        let error = || Error::InvalidEnPassant {
+            value: String::from(value),
+        };
+
+        if value == "-" {
+            return Ok(());
+        }
+
+        let target = Coordinate::from_alphanumeric(value).map_err(|_| error())?;
+
+        // the target is the square the pawn skipped over
+        let (target_rank, pawn_rank) = match white {
+            true => (NUM_ROWS - 3, NUM_ROWS - 4),
+            false => (2, 3),
+        };
+        if target.y != target_rank || board.grid[target.y][target.x].is_some() {
+            return Err(error());
+        }
+
+        match &board.grid[pawn_rank][target.x] {
+            Some(piece) if piece.id == Id::Pawn && piece.white != white => (),
+            _ => return Err(error()),
+        }
+
+        board.state.en_passant = Some(target);
+        Ok(())
+    }
+
+    /// Finds the file of the king on a rank
+    fn find_king(board: &Board, rank: usize, white: bool) -> Option<usize> {
+        board.grid[rank]
+            .iter()
+            .flatten()
+            .find(|piece| piece.id == Id::King && piece.white == white)
+            .map(|piece| piece.position.x)
+    }
+
+    fn is_rook(board: &Board, x: usize, y: usize, white: bool) -> bool {
+        match &board.grid[y][x] {
+            Some(piece) => piece.id == Id::Rook && piece.white == white,
+            None => false,
+        }
+    }
+
+    /// Converts the board to Forsyth-Edwards Notation
+    /// * the en passant square is only given if the capture can be made
+    pub fn to_fen(&self) -> String {
+        let white = self.state.white;
+        let mut ranks: Vec<String> = Vec::new();
+        for y in (0..NUM_ROWS).rev() {
+            let mut rank = String::new();
+            let mut empty = 0;
+            for x in 0..NUM_COLS {
+                match &self.grid[y][x] {
+                    Some(piece) => {
+                        if empty > 0 {
+                            rank.push_str(&empty.to_string());
+                            empty = 0;
+                        }
+                        rank.push(Self::fen_letter(piece));
+                    }
+                    None => empty += 1,
+                }
+            }
+
+            if empty > 0 {
+                rank.push_str(&empty.to_string());
+            }
+            ranks.push(rank);
+        }
+
+        let mut castling = String::new();
+        for colour in [true, false] {
+            castling.push_str(&self.castling_rights(colour));
+        }
+        if castling.is_empty() {
+            castling.push('-');
+        }
+
+        let mut en_passant = String::from("-");
+        for move_type in self.legal_moves() {
+            if let MoveType::EnPassant { target, .. } = move_type {
+                en_passant = target.to_alphanumeric();
+                break;
+            }
+        }
+
+        format!(
+            "{} {} {} {} {} {}",
+            ranks.join("/"),
+            if white { "w" } else { "b" },
+            castling,
+            en_passant,
+            self.state.halfmove_clock,
+            self.state.fullmove_number
+        )
+    }
+
+    /// Uppercase letters for white pieces, lowercase for black
+    fn fen_letter(piece: &Piece) -> char {
+        let letter = piece.id.to_char().unwrap_or('P');
+        match piece.white {
+            true => letter,
+            false => letter.to_ascii_lowercase(),
+        }
+    }
+
+    /// Castling rights for one colour, kingside first
+    /// * rooks are given by file unless they are the outermost rook on that side
+    fn castling_rights(&self, white: bool) -> String {
+        let rank = if white { 0 } else { NUM_ROWS - 1 };
+        let mut rights = String::new();
+
+        let king_x = match Self::find_king(self, rank, white) {
+            Some(x) => x,
+            None => return rights,
+        };
+
+        let mut files = self.state.castling_files(white);
+        files.sort_by_key(|x| std::cmp::Reverse(*x));
+        for kingside in [true, false] {
+            let outermost = match kingside {
+                true => (king_x + 1..NUM_COLS)
+                    .rev()
+                    .find(|x| Self::is_rook(self, *x, rank, white)),
+                false => (0..king_x).find(|x| Self::is_rook(self, *x, rank, white)),
+            };
+
+            for x in &files {
+                if (*x > king_x) != kingside {
+                    continue;
+                }
+
+                let letter = match (Some(*x) == outermost, kingside) {
+                    (true, true) => 'K',
+                    (true, false) => 'Q',
+                    (false, _) => (b'A' + *x as u8) as char,
+                };
+                rights.push(match white {
+                    true => letter,
+                    false => letter.to_ascii_lowercase(),
+                });
+            }
+        }
+
+        rights
+    }
+}
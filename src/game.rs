@@ -0,0 +1,160 @@
+use crate::board::Board;
+use crate::pieces::{Id, MoveChecker, Piece};
+use std::fmt;
+
+// the fifty-move rule counts moves by both players
+const FIFTY_MOVES: usize = 100;
+const REPETITIONS: usize = 3;
+
+/// Reasons for a game to end
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Termination {
+    Checkmate,
+    Stalemate,
+    ThreefoldRepetition,
+    FiftyMoveRule,
+    InsufficientMaterial,
+    Timeout,
+}
+
+/// Outcome of a finished game
+/// * `winner` - `Some(true)` if white won, `Some(false)` if black won, `None` for a draw
+/// * `termination` - [Termination]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GameResult {
+    pub winner: Option<bool>,
+    pub termination: Termination,
+}
+
+impl GameResult {
+    /// Checks if the game is over with the side to move
+    /// * returns `None` if the game can continue
+    pub fn from_board(board: &Board) -> Option<GameResult> {
+        let white = board.state.white;
+        if board.legal_moves().is_empty() {
+            return match MoveChecker::in_check(board, white) {
+                true => Some(GameResult {
+                    winner: Some(!white),
+                    termination: Termination::Checkmate,
+                }),
+                false => Some(Self::draw(Termination::Stalemate)),
+            };
+        }
+
+        // running out of time loses, unless the opponent couldn't have won anyway
+        match &board.clock {
+            Some(clock) if clock.flagged(white) => {
+                return match can_mate(board, !white) {
+                    true => Some(GameResult {
+                        winner: Some(!white),
+                        termination: Termination::Timeout,
+                    }),
+                    false => Some(Self::draw(Termination::Timeout)),
+                };
+            }
+            _ => (),
+        }
+
+        if insufficient_material(board) {
+            return Some(Self::draw(Termination::InsufficientMaterial));
+        }
+
+        if board.state.halfmove_clock >= FIFTY_MOVES {
+            return Some(Self::draw(Termination::FiftyMoveRule));
+        }
+
+        if let Some(hash) = board.positions.last() {
+            let count = board
+                .positions
+                .iter()
+                .filter(|other| *other == hash)
+                .count();
+            if count >= REPETITIONS {
+                return Some(Self::draw(Termination::ThreefoldRepetition));
+            }
+        }
+
+        None
+    }
+
+    /// Result token used in PGN
+    pub fn score(&self) -> &'static str {
+        match self.winner {
+            Some(true) => "1-0",
+            Some(false) => "0-1",
+            None => "1/2-1/2",
+        }
+    }
+
+    fn draw(termination: Termination) -> GameResult {
+        GameResult {
+            winner: None,
+            termination,
+        }
+    }
+}
+
+impl fmt::Display for GameResult {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.winner {
+            Some(white) => {
+                let winner = if white { "White" } else { "Black" };
+                match self.termination {
+                    Termination::Timeout => write!(f, "{} has won on time!", winner),
+                    _ => write!(f, "{} has won!", winner),
+                }
+            }
+            None => {
+                let reason = match self.termination {
+                    Termination::Stalemate => "stalemate",
+                    Termination::ThreefoldRepetition => "threefold repetition",
+                    Termination::FiftyMoveRule => "the fifty-move rule",
+                    Termination::InsufficientMaterial => "insufficient material",
+                    Termination::Timeout => "timeout against insufficient material",
+                    // checkmate always has a winner
+                    Termination::Checkmate => "checkmate",
+                };
+                write!(f, "Draw by {}!", reason)
+            }
+        }
+    }
+}
+
+/// Checks if neither side has enough pieces left to deliver checkmate
+/// * lone kings, a single minor piece, or only bishops on the same colour
+pub fn insufficient_material(board: &Board) -> bool {
+    let mut minors: Vec<&Piece> = Vec::new();
+    for row in &board.grid {
+        for piece in row.iter().flatten() {
+            match piece.id {
+                Id::King => (),
+                Id::Bishop | Id::Knight => minors.push(piece),
+                _ => return false,
+            }
+        }
+    }
+
+    if minors.len() <= 1 {
+        return true;
+    }
+
+    // any number of bishops can't mate if they all share a square colour
+    let colour = |piece: &Piece| (piece.position.x + piece.position.y) % 2;
+    minors
+        .iter()
+        .all(|piece| piece.id == Id::Bishop && colour(piece) == colour(minors[0]))
+}
+
+/// Checks if one side could still deliver checkmate, for losses on time
+/// * any piece besides the king can mate with help from the opponent's pieces
+/// * unless the position is dead, see [insufficient_material]
+fn can_mate(board: &Board, white: bool) -> bool {
+    let material = board
+        .grid
+        .iter()
+        .flatten()
+        .flatten()
+        .any(|piece| piece.white == white && piece.id != Id::King);
+
+    material && !insufficient_material(board)
+}
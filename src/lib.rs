@@ -1,11 +1,24 @@
-pub enum Error {
-    InvalidArgument,
-    IndexOutOfRange,
-    InvalidMove { message: String }
-}
-
+pub mod attacks;
+pub mod bitboard;
 pub mod board;
+pub mod book;
+pub mod clock;
 pub mod coordinate;
+pub mod endgame;
+pub mod error;
+pub mod eval;
+pub mod explain;
+pub mod fen;
+pub mod game;
+pub mod perft;
+pub mod pgn;
+pub mod san;
+pub mod search;
+pub mod start;
+pub mod state;
+pub mod transposition;
+pub mod uci;
+pub mod zobrist;
 pub mod pieces {
     pub mod moves;
     pub use moves::MoveChecker;
@@ -15,6 +28,8 @@ pub mod pieces {
     pub use piece::Piece;
 }
 
+pub use error::{Castling, Error, Line, MoveError};
+
 #[cfg(test)]
 mod tests {
     use crate::board::Board;
@@ -22,6 +37,7 @@ mod tests {
     use crate::pieces::MoveChecker;
 
     /// Tests if a piece at (x1, y1) can move to (x2, y2)
+    #[allow(clippy::assertions_on_constants, clippy::needless_borrow)]
     fn test_move(board: &Board, x1: usize, y1: usize, x2: usize, y2: usize, expected: bool) {
         let position = Coordinate { x: x2, y: y2 };
         match &board.grid[y1][x1] {
@@ -33,12 +49,45 @@ mod tests {
         }
     }
 
+    /// Sets up a board from a FEN string that is known to be valid
+    fn from_fen(fen: &str) -> Board {
+        Board::from_fen(fen).ok().unwrap()
+    }
+
+    /// Reads a square written like `e4`
+    fn square(name: &str) -> Coordinate {
+        Coordinate::from_alphanumeric(name).ok().unwrap()
+    }
+
+    mod attacks;
     mod bishop_moves;
+    mod bitboard;
+    mod book;
+    mod chess960;
+    mod clock;
+    mod endgame;
+    mod error;
+    mod eval;
+    mod explain;
+    mod fen;
+    mod game_result;
     mod king_moves;
     mod knight_moves;
+    mod legal_moves;
     mod pawn_moves;
     mod queen_moves;
     mod rook_moves;
+    mod san;
+    mod search;
+    mod see;
+    mod start;
+    mod state;
+    mod transposition;
+    mod uci;
+    mod undo;
+    mod zobrist;
 
     mod parse_moves;
+    mod perft;
+    mod pgn;
 }
@@ -1,30 +1,230 @@
 use chess::board::*;
+use chess::book::{self, Book};
+use chess::clock::{Clock, TimeControl};
+use chess::endgame::{self, Dtm, Endgames};
+use chess::pgn::Pgn;
+use chess::pieces::moves::MoveType;
+use chess::search::{Limits, Search};
+use chess::start::Variant;
+use chess::zobrist;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
 use std::error::Error;
-use std::io;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use std::{fs, io};
 
 fn main() -> Result<(), Box<dyn Error>> {
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+
+    // books made with other keys need those keys, which have to be in place before any board
+    if let Some(path) = take_option(&mut args, "--keys") {
+        let keys = zobrist::parse_keys(&fs::read_to_string(&path)?);
+        if !keys.is_some_and(|keys| zobrist::use_keys(&keys)) {
+            return Err(format!("{} should have {} keys", path, zobrist::NUM_KEYS).into());
+        }
+    }
+
+    let book = match take_option(&mut args, "--book") {
+        Some(path) => Some(Book::open(&path)?),
+        None => None,
+    };
+
+    let mut endgames = match take_option(&mut args, "--endgames") {
+        Some(path) => Endgames::open(Path::new(&path))?,
+        None => Endgames::new(),
+    };
+
+    // `chess perft <depth> [fen]` counts moves instead of starting a game
+    // `chess book <games> <book> [plies]` makes an opening book
+    // `chess endgame <directory> <material>...` makes distance to mate tables
+    match args.first().map(String::as_str) {
+        Some("perft") => return perft(&args[1..]),
+        Some("book") => return build_book(&args[1..]),
+        Some("endgame") => return build_endgames(&args[1..]),
+        _ => (),
+    }
+
+    // chess960 games can be picked by number, and shuffled starts made repeatable with a seed
+    let position = match take_option(&mut args, "--chess960") {
+        Some(id) => match id.parse::<u16>() {
+            Ok(id) if id < CHESS960_POSITIONS => Some(Board::new_chess960(id)),
+            _ => return Err(format!("{} is not a chess960 position, use 0 to 959", id).into()),
+        },
+        None => None,
+    };
+    let mut rng = match take_option(&mut args, "--seed") {
+        Some(seed) => match seed.parse::<u64>() {
+            Ok(seed) => StdRng::seed_from_u64(seed),
+            Err(_) => return Err(format!("{} is not a valid seed", seed).into()),
+        },
+        None => StdRng::from_entropy(),
+    };
+
     let stdin = io::stdin();
     let mut line = String::new();
 
-    // select game type
-    println!("\u{001b}[5mGame Select: \u{001b}[0m");
-    println!("1. Chess");
-    println!("2. Chess960");
+    // select game type, unless the position was given
+    let (mut board, mut variant) = match position {
+        Some(board) => (board, Some(Variant::Chess960)),
+        None => {
+            println!("\u{001b}[5mGame Select: \u{001b}[0m");
+            println!("1. Chess");
+            for (i, variant) in Variant::ALL.iter().enumerate() {
+                println!("{}. {}", i + 2, variant.name());
+            }
+            stdin.read_line(&mut line)?;
+            let choice = String::from(line.trim());
+            line.clear();
+            if choice == "quit" {
+                return Ok(());
+            }
+
+            match choice.parse::<usize>() {
+                Ok(choice) if (2..Variant::ALL.len() + 2).contains(&choice) => {
+                    let variant = Variant::ALL[choice - 2];
+                    (Board::new_variant(variant, &mut rng), Some(variant))
+                }
+                _ => (Board::new(), None),
+            }
+        }
+    };
+    board.message = match (variant, board.chess960_id()) {
+        (Some(Variant::Chess960), Some(id)) => format!("Chess960 position {}", id),
+        (Some(variant), _) => String::from(variant.name()),
+        (None, _) => String::new(),
+    };
+
+    // select who plays each side
+    println!("\u{001b}[5mMode Select: \u{001b}[0m");
+    println!("1. Human vs Human");
+    println!("2. Human vs Computer");
+    println!("3. Computer vs Computer");
+    println!("4. Perfect Play");
     stdin.read_line(&mut line)?;
-    let choice = line.trim();
-    let mut board = match choice {
+    let mode = String::from(line.trim());
+    line.clear();
+
+    // endgames set up from a FEN, against a computer playing from the tables
+    if mode == "4" {
+        println!("\u{001b}[5mEndgame FEN: \u{001b}[0m");
+        stdin.read_line(&mut line)?;
+        board = match Board::from_fen(line.trim()) {
+            Ok(board) => board,
+            Err(error) => return Err(format!("invalid FEN: {}", error).into()),
+        };
+        line.clear();
+        variant = None;
+
+        println!("Generating tables for {}...", endgame::name(&board));
+        endgames.generate(&endgame::name(&board))?;
+    }
+    let endgames = match endgames.names().is_empty() {
+        true => None,
+        false => Some(Arc::new(endgames)),
+    };
+
+    // the side the computer plays, for each colour
+    let computer = match mode.as_str() {
         "quit" => return Ok(()),
-        "2" => Board::new_random(),
-        _ => Board::new(),
+        "4" => [!board.state.white, board.state.white],
+        "2" => {
+            println!("\u{001b}[5mColour Select: \u{001b}[0m");
+            println!("1. White");
+            println!("2. Black");
+            stdin.read_line(&mut line)?;
+            let black = line.trim() == "2";
+            line.clear();
+            [black, !black]
+        }
+        "3" => [true, true],
+        _ => [false, false],
+    };
+
+    let limits = match computer {
+        [false, false] => Limits::default(),
+        _ if mode == "4" => Limits::default(),
+        _ => {
+            println!("\u{001b}[5mStrength Select: \u{001b}[0m");
+            println!("1. Easy");
+            println!("2. Medium");
+            println!("3. Hard");
+            stdin.read_line(&mut line)?;
+            let level = strength(line.trim());
+            line.clear();
+            level
+        }
+    };
+
+    println!("\u{001b}[5mTime Control Select: \u{001b}[0m");
+    println!("1. Untimed");
+    for (i, control) in TIME_CONTROLS.iter().enumerate() {
+        println!("{}. {}", i + 2, control);
+    }
+    stdin.read_line(&mut line)?;
+    board.clock = match line.trim().parse::<usize>() {
+        Ok(choice) if (2..TIME_CONTROLS.len() + 2).contains(&choice) => {
+            Some(Clock::new(TIME_CONTROLS[choice - 2]))
+        }
+        _ => None,
     };
     line.clear();
 
-    // keep track of turns
-    let mut white = true;
+    // humans see the board from their own side
+    let perspective = |white: bool| match computer {
+        [true, false] => false,
+        [false, true] => true,
+        _ => white,
+    };
+
+    // saved games need to know where they started
+    let mut start = board.to_fen();
+    if let Some(clock) = &mut board.clock {
+        clock.start(board.state.white);
+    }
 
     loop {
+        // the board keeps track of turns
+        let white = board.state.white;
+
         // display board
-        board.show(white);
+        board.show(perspective(white));
+
+        // the computer's thinking time stands in for a pause between turns
+        if computer[if white { 0 } else { 1 }] {
+            println!("Thinking...");
+            let mut limits = limits.clone();
+            if let Some(clock) = &board.clock {
+                let allocated = clock.allocate(white);
+                limits.time = Some(limits.time.map_or(allocated, |time| time.min(allocated)));
+            }
+
+            // openings come from the book while it has moves for the position
+            let book_move = book
+                .as_ref()
+                .and_then(|book| book.choose(&board, &mut rand::thread_rng()));
+            let (best_move, source) = match book_move {
+                Some(book_move) => (book_move, " from the book"),
+                None => match think(&board, limits, &endgames) {
+                    Some(best_move) => (best_move, ""),
+                    None => break,
+                },
+            };
+            if board.flagged() {
+                println!("{}", export(&board, &start, variant));
+                break;
+            }
+            let san = board.to_san(&best_move);
+            board.play(&best_move);
+            board.message = format!("Computer played {}{}", san, source);
+
+            if board.game_over() {
+                println!("{}", export(&board, &start, variant));
+                break;
+            }
+            continue;
+        }
 
         // wait for input
         println!(
@@ -38,16 +238,64 @@ fn main() -> Result<(), Box<dyn Error>> {
             break;
         }
 
-        if board.make_move(input, white) {
-            board.show(white);
-            board.show_loading_bar();
+        // the clock can only be checked once the player has typed something
+        if board.flagged() {
+            println!("{}", export(&board, &start, variant));
+            break;
+        }
 
-            if board.game_over(white) {
-                break;
+        // take back moves, including the computer's reply so it's the human's turn again
+        if input == "undo" || input == "takeback" || input == "redo" {
+            let step = |board: &mut Board| match input {
+                "redo" => board.redo(),
+                _ => board.undo_move(),
+            };
+            board.message.clear();
+            if step(&mut board) {
+                while computer[if board.state.white { 0 } else { 1 }] && step(&mut board) {}
+            } else {
+                board.message = format!("Nothing to {}", input);
+            }
+            restart_clock(&mut board);
+            line.clear();
+            continue;
+        }
+
+        // save the game so far as PGN
+        if let Some(path) = input.strip_prefix("save ") {
+            let pgn = export(&board, &start, variant);
+            board.message = match fs::write(path.trim(), pgn.to_string()) {
+                Ok(()) => format!("Saved game to {}", path.trim()),
+                Err(error) => format!("Could not save game: {}", error),
+            };
+            line.clear();
+            continue;
+        }
+
+        // continue from the end of a PGN game
+        if let Some(path) = input.strip_prefix("load ") {
+            match load(path.trim()) {
+                Ok((pgn, loaded)) => {
+                    start = match pgn.tag("FEN") {
+                        Some(fen) => String::from(fen),
+                        None => chess::fen::STARTING_FEN.to_string(),
+                    };
+                    variant = pgn.tag("Variant").and_then(Variant::from_name);
+                    let clock = board.clock.take();
+                    board = loaded;
+                    board.clock = clock;
+                    restart_clock(&mut board);
+                    board.message = format!("Loaded game from {}", path.trim());
+                }
+                Err(message) => board.message = message,
             }
+            line.clear();
+            continue;
+        }
 
-            // only change to the other player after a valid move is made
-            white = !white;
+        if board.make_move(input) && board.game_over() {
+            println!("{}", export(&board, &start, variant));
+            break;
         }
 
         line.clear();
@@ -55,3 +303,194 @@ fn main() -> Result<(), Box<dyn Error>> {
 
     Ok(())
 }
+
+/// Searches for the computer's move, looking endgames up in the tables if there are any
+fn think(board: &Board, limits: Limits, endgames: &Option<Arc<Endgames>>) -> Option<MoveType> {
+    let mut search = Search::new(limits);
+    search.endgames = endgames.clone();
+    search.run(board, |_| ()).best_move
+}
+
+/// Removes `--name <value>` from the arguments, returning the value
+fn take_option(args: &mut Vec<String>, name: &str) -> Option<String> {
+    let i = args.iter().position(|arg| arg == name)?;
+    if i + 1 >= args.len() {
+        args.remove(i);
+        return None;
+    }
+
+    let value = args.remove(i + 1);
+    args.remove(i);
+    Some(value)
+}
+
+/// Makes a Polyglot book from the opening moves of a PGN collection
+/// * `args` - the PGN file, the book file to write and optionally how many plies to add
+fn build_book(args: &[String]) -> Result<(), Box<dyn Error>> {
+    let (games, path) = match args {
+        [games, path, ..] => (games, path),
+        _ => return Err("usage: chess book <games.pgn> <book.bin> [plies]".into()),
+    };
+    let plies = match args.get(2) {
+        Some(plies) => plies.parse()?,
+        None => book::DEFAULT_PLIES,
+    };
+
+    let games = Pgn::parse_all(&fs::read_to_string(games)?)?;
+    let book = Book::build(&games, plies);
+    book.save(path)?;
+    println!(
+        "Added {} moves from {} games to {}",
+        book.entries.len(),
+        games.len(),
+        path
+    );
+    Ok(())
+}
+
+/// Makes distance to mate tables, along with the smaller ones they lead to, and saves them
+/// * `args` - the directory to write to, followed by endings like `KQvK`
+fn build_endgames(args: &[String]) -> Result<(), Box<dyn Error>> {
+    let (directory, materials) = match args.split_first() {
+        Some((directory, materials)) if !materials.is_empty() => (directory, materials),
+        _ => return Err("usage: chess endgame <directory> <material>...".into()),
+    };
+
+    let mut endgames = Endgames::new();
+    for material in materials {
+        let start = Instant::now();
+        endgames.generate(material)?;
+        println!(
+            "Generated {} in {}ms",
+            material,
+            start.elapsed().as_millis()
+        );
+    }
+
+    fs::create_dir_all(directory)?;
+    endgames.save(Path::new(directory))?;
+    for name in endgames.names() {
+        let longest = endgames.get(&name).and_then(|endgame| endgame.longest());
+        match longest {
+            Some(Dtm::Win(plies)) => println!("{}: longest mate in {}", name, plies.div_ceil(2)),
+            _ => println!("{}: no forced mates", name),
+        }
+    }
+    Ok(())
+}
+
+/// Prints the node count after each first move, then the total
+/// * `args` - the depth, followed by an optional FEN for the starting position
+fn perft(args: &[String]) -> Result<(), Box<dyn Error>> {
+    let depth: usize = match args.first() {
+        Some(depth) => depth.parse()?,
+        None => return Err("usage: chess perft <depth> [fen]".into()),
+    };
+    let board = match args.len() {
+        1 => Board::new(),
+        _ => match Board::from_fen(&args[1..].join(" ")) {
+            Ok(board) => board,
+            Err(error) => return Err(format!("invalid FEN: {}", error).into()),
+        },
+    };
+
+    let start = Instant::now();
+    let white = board.state.white;
+    let mut total = 0;
+    for (move_type, nodes) in board.divide(depth) {
+        println!("{}: {}", move_type.to_uci(white, false), nodes);
+        total += nodes;
+    }
+    if depth == 0 {
+        total = 1;
+    }
+
+    println!();
+    println!("Nodes searched: {}", total);
+    println!("Time: {}ms", start.elapsed().as_millis());
+    Ok(())
+}
+
+/// Time controls to choose from, after playing untimed
+const TIME_CONTROLS: [TimeControl; 4] = [
+    TimeControl::SuddenDeath {
+        base: Duration::from_secs(5 * 60),
+    },
+    TimeControl::Fischer {
+        base: Duration::from_secs(3 * 60),
+        increment: Duration::from_secs(2),
+    },
+    TimeControl::Bronstein {
+        base: Duration::from_secs(15 * 60),
+        delay: Duration::from_secs(10),
+    },
+    TimeControl::MovesPerPeriod {
+        base: Duration::from_secs(90 * 60),
+        moves: 40,
+    },
+];
+
+/// Moves the running clock over to the side to move, after moves are taken back or loaded
+fn restart_clock(board: &mut Board) {
+    if let Some(clock) = &mut board.clock {
+        clock.stop();
+        clock.start(board.state.white);
+    }
+}
+
+/// Search limits for each strength level
+fn strength(level: &str) -> Limits {
+    match level {
+        "1" => Limits {
+            depth: Some(1),
+            time: None,
+        },
+        "3" => Limits {
+            depth: None,
+            time: Some(Duration::from_secs(5)),
+        },
+        _ => Limits {
+            depth: Some(3),
+            time: Some(Duration::from_secs(2)),
+        },
+    }
+}
+
+/// Builds the PGN for the game so far
+/// * `variant` - how the starting position was shuffled, if it was
+fn export(board: &Board, start: &str, variant: Option<Variant>) -> Pgn {
+    let mut pgn = Pgn::from_board(board, start);
+    if let Some(variant) = variant {
+        pgn.set_tag("Variant", variant.name());
+    }
+
+    // chess960 games also give the number of their starting position
+    let id = Board::from_fen(start).map(|start| start.chess960_id());
+    if let (Some(Variant::Chess960), Ok(Some(id))) = (variant, id) {
+        pgn.set_tag("StartPosition", &id.to_string());
+    }
+
+    if let Some(result) = board.game_result() {
+        pgn.set_tag("Result", result.score());
+    }
+
+    pgn
+}
+
+/// Reads a PGN file and replays it
+fn load(path: &str) -> Result<(Pgn, Board), String> {
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(error) => return Err(format!("Could not load game: {}", error)),
+    };
+
+    let replayed = Pgn::parse(&text).and_then(|pgn| {
+        let board = pgn.replay()?;
+        Ok((pgn, board))
+    });
+
+    match replayed {
+        Ok(replayed) => Ok(replayed),
+        Err(error) => Err(format!("Could not load game: {}", error)),
+    }
+}
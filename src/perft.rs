@@ -0,0 +1,52 @@
+use crate::board::Board;
+use crate::pieces::moves::MoveType;
+
+impl Board {
+    /// Counts the positions reached after `depth` moves by either side, for the side to move
+    /// * compared against known totals to check move generation
+    pub fn perft(&self, depth: usize) -> u64 {
+        count(&self.position(), depth)
+    }
+
+    /// Splits [Board::perft] by the first move, which narrows down where a count goes wrong
+    pub fn divide(&self, depth: usize) -> Vec<(MoveType, u64)> {
+        let board = self.position();
+        let white = board.state.white;
+        if depth == 0 {
+            return Vec::new();
+        }
+
+        board
+            .legal_moves()
+            .into_iter()
+            .map(|move_type| {
+                let mut child = board.clone();
+                child.apply_move(&move_type, white);
+                let nodes = count(&child, depth - 1);
+                (move_type, nodes)
+            })
+            .collect()
+    }
+}
+
+fn count(board: &Board, depth: usize) -> u64 {
+    if depth == 0 {
+        return 1;
+    }
+
+    let white = board.state.white;
+    let moves = board.legal_moves();
+
+    // the last moves don't need to be made to be counted
+    if depth == 1 {
+        return moves.len() as u64;
+    }
+
+    let mut nodes = 0;
+    for move_type in moves {
+        let mut child = board.clone();
+        child.apply_move(&move_type, white);
+        nodes += count(&child, depth - 1);
+    }
+    nodes
+}
@@ -0,0 +1,350 @@
+use crate::board::Board;
+use crate::fen::STARTING_FEN;
+use crate::Error;
+use std::fmt;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Tags every PGN game is expected to have, in export order
+pub const SEVEN_TAG_ROSTER: [&str; 7] =
+    ["Event", "Site", "Date", "Round", "White", "Black", "Result"];
+const RESULTS: [&str; 4] = ["1-0", "0-1", "1/2-1/2", "*"];
+
+// export format keeps movetext lines below 80 characters
+const LINE_WIDTH: usize = 79;
+
+/// A game in Portable Game Notation
+/// * `tags` - name and value pairs in the order they were given
+/// * `moves` - moves in algebraic notation, without move numbers
+/// * `result` - `1-0`, `0-1`, `1/2-1/2` or `*` for an unfinished game
+pub struct Pgn {
+    pub tags: Vec<(String, String)>,
+    pub moves: Vec<String>,
+    pub result: String,
+}
+
+impl Default for Pgn {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Pgn {
+    /// Creates a game with the seven tag roster filled with placeholders
+    pub fn new() -> Pgn {
+        let mut pgn = Pgn {
+            tags: Vec::new(),
+            moves: Vec::new(),
+            result: String::from("*"),
+        };
+
+        for name in SEVEN_TAG_ROSTER {
+            let value = match name {
+                "Date" => "????.??.??",
+                "Result" => "*",
+                _ => "?",
+            };
+            pgn.set_tag(name, value);
+        }
+
+        pgn
+    }
+
+    /// Records the moves made on a board
+    /// * `start` - FEN of the position the game started from
+    pub fn from_board(board: &Board, start: &str) -> Pgn {
+        let mut pgn = Self::new();
+        pgn.set_tag("Date", &today());
+        pgn.moves = board.history.clone();
+
+        if start != STARTING_FEN {
+            pgn.set_tag("SetUp", "1");
+            pgn.set_tag("FEN", start);
+        }
+
+        pgn
+    }
+
+    /// Gets the value of a tag
+    pub fn tag(&self, name: &str) -> Option<&str> {
+        self.tags
+            .iter()
+            .find(|(tag, _)| tag == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Adds a tag or replaces its value
+    /// * also updates the game result if the tag is `Result`
+    pub fn set_tag(&mut self, name: &str, value: &str) {
+        if name == "Result" {
+            self.result = String::from(value);
+        }
+
+        match self.tags.iter_mut().find(|(tag, _)| tag == name) {
+            Some((_, old)) => *old = String::from(value),
+            None => self.tags.push((String::from(name), String::from(value))),
+        }
+    }
+
+    /// Reads a single game
+    /// * comments, variations, move numbers and annotation glyphs are skipped
+    pub fn parse(text: &str) -> Result<Pgn, Error> {
+        let mut pgn = Pgn {
+            tags: Vec::new(),
+            moves: Vec::new(),
+            result: String::from("*"),
+        };
+
+        let mut chars = text.chars();
+        let mut depth = 0;
+        let mut token = String::new();
+
+        // tokens are only complete when followed by a separator
+        let mut tokens: Vec<String> = Vec::new();
+        let flush = |token: &mut String, tokens: &mut Vec<String>| {
+            if !token.is_empty() {
+                tokens.push(token.clone());
+                token.clear();
+            }
+        };
+
+        while let Some(c) = chars.next() {
+            match c {
+                '[' if depth == 0 && tokens.is_empty() && token.is_empty() => {
+                    let (name, value) = Self::parse_tag(&mut chars)?;
+                    pgn.set_tag(&name, &value);
+                }
+                '{' => {
+                    flush(&mut token, &mut tokens);
+                    if !chars.by_ref().any(|c| c == '}') {
+                        return Err(Self::error("comment is never closed"));
+                    }
+                }
+                ';' => {
+                    flush(&mut token, &mut tokens);
+                    for c in chars.by_ref() {
+                        if c == '\n' {
+                            break;
+                        }
+                    }
+                }
+                '(' => {
+                    flush(&mut token, &mut tokens);
+                    depth += 1;
+                }
+                ')' => {
+                    flush(&mut token, &mut tokens);
+                    if depth == 0 {
+                        return Err(Self::error("variation is never opened"));
+                    }
+                    depth -= 1;
+                }
+                _ if depth > 0 => (),
+                c if c.is_whitespace() => flush(&mut token, &mut tokens),
+                c => {
+                    token.push(c);
+
+                    // move numbers can be written right next to the move, e.g. 1.e4
+                    if c == '.' && Self::is_move_number(&token) {
+                        flush(&mut token, &mut tokens);
+                    }
+                }
+            }
+        }
+        flush(&mut token, &mut tokens);
+
+        if depth > 0 {
+            return Err(Self::error("variation is never closed"));
+        }
+
+        for token in tokens {
+            if RESULTS.contains(&token.as_str()) {
+                pgn.result = token;
+                break;
+            }
+
+            // move numbers, numeric annotation glyphs and en passant markers
+            if Self::is_move_number(&token) || token.starts_with('$') || token == "e.p." {
+                continue;
+            }
+
+            let token = token.trim_end_matches(['!', '?']);
+            if !token.is_empty() {
+                pgn.moves.push(String::from(token));
+            }
+        }
+
+        Ok(pgn)
+    }
+
+    /// Reads every game in a collection, e.g. a database export
+    /// * a game ends where the tags of the next one start
+    pub fn parse_all(text: &str) -> Result<Vec<Pgn>, Error> {
+        let mut games: Vec<Pgn> = Vec::new();
+        let mut game = String::new();
+        let mut movetext = false;
+        for line in text.lines() {
+            let tag = line.trim_start().starts_with('[');
+            if tag && movetext {
+                games.push(Self::parse(&game)?);
+                game.clear();
+                movetext = false;
+            }
+
+            movetext |= !tag && !line.trim().is_empty();
+            game.push_str(line);
+            game.push('\n');
+        }
+
+        if !game.trim().is_empty() {
+            games.push(Self::parse(&game)?);
+        }
+        Ok(games)
+    }
+
+    fn is_move_number(token: &str) -> bool {
+        token.chars().all(|c| c.is_ascii_digit() || c == '.')
+    }
+
+    /// Reads the rest of a tag pair after the opening bracket
+    fn parse_tag(chars: &mut impl Iterator<Item = char>) -> Result<(String, String), Error> {
+        let mut name = String::new();
+        let mut value = String::new();
+        let mut quoted = false;
+        let mut escaped = false;
+
+        for c in chars {
+            if quoted {
+                match c {
+                    _ if escaped => {
+                        value.push(c);
+                        escaped = false;
+                    }
+                    '\\' => escaped = true,
+                    '"' => quoted = false,
+                    _ => value.push(c),
+                }
+                continue;
+            }
+
+            match c {
+                ']' => {
+                    if name.is_empty() {
+                        return Err(Self::error("tag is missing a name"));
+                    }
+                    return Ok((name, value));
+                }
+                '"' => quoted = true,
+                c if c.is_whitespace() => (),
+                c => name.push(c),
+            }
+        }
+
+        Err(Self::error("tag is never closed"))
+    }
+
+    fn error(message: &str) -> Error {
+        Error::InvalidPgn {
+            message: String::from(message),
+        }
+    }
+
+    /// Plays the moves onto a board, starting from the `FEN` tag if there is one
+    pub fn replay(&self) -> Result<Board, Error> {
+        let mut board = match self.tag("FEN") {
+            Some(fen) => Board::from_fen(fen)?,
+            None => Board::new(),
+        };
+
+        for (ply, input) in self.moves.iter().enumerate() {
+            match board.try_move(input) {
+                Ok(()) => (),
+                Err(error) => {
+                    return Err(Error::IllegalMove {
+                        ply: ply + 1,
+                        input: input.clone(),
+                        message: error.reason(),
+                    })
+                }
+            }
+        }
+
+        Ok(board)
+    }
+
+    /// Numbers the moves, starting from the position in the `FEN` tag if there is one
+    /// * each move number is kept with the move it belongs to
+    fn movetext(&self) -> Vec<String> {
+        let (mut number, mut white) = match self.tag("FEN").map(Board::from_fen) {
+            Some(Ok(board)) => (board.state.fullmove_number, board.state.white),
+            _ => (1, true),
+        };
+
+        let mut tokens: Vec<String> = Vec::new();
+        for (i, input) in self.moves.iter().enumerate() {
+            match (white, i) {
+                (true, _) => tokens.push(format!("{}. {}", number, input)),
+                (false, 0) => tokens.push(format!("{}... {}", number, input)),
+                (false, _) => tokens.push(input.clone()),
+            }
+
+            if !white {
+                number += 1;
+            }
+            white = !white;
+        }
+
+        tokens.push(self.result.clone());
+        tokens
+    }
+}
+
+impl fmt::Display for Pgn {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (name, value) in &self.tags {
+            let value = value.replace('\\', "\\\\").replace('"', "\\\"");
+            writeln!(f, "[{} \"{}\"]", name, value)?;
+        }
+        writeln!(f)?;
+
+        let mut line = String::new();
+        for token in self.movetext() {
+            if !line.is_empty() && line.len() + token.len() + 1 > LINE_WIDTH {
+                writeln!(f, "{}", line)?;
+                line.clear();
+            }
+
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line.push_str(&token);
+        }
+
+        writeln!(f, "{}", line)
+    }
+}
+
+/// Today's date in the format used by the `Date` tag
+fn today() -> String {
+    let seconds = match SystemTime::now().duration_since(UNIX_EPOCH) {
+        Ok(duration) => duration.as_secs(),
+        Err(_) => return String::from("????.??.??"),
+    };
+
+    // convert days since 1970-01-01 to a civil date
+    let days = (seconds / 86400) as i64 + 719468;
+    let era = days / 146097;
+    let day_of_era = days - era * 146097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!("{:04}.{:02}.{:02}", year, month, day)
+}
@@ -1,8 +1,12 @@
+use crate::attacks;
+use crate::bitboard;
 use crate::board::*;
 use crate::coordinate::Coordinate;
 use crate::pieces::{Id, Piece};
+use crate::state::State;
 
 /// Contains the data needed to make a type of move
+#[derive(Clone, PartialEq)]
 pub enum MoveType {
     Normal {
         piece: Piece,
@@ -21,6 +25,138 @@ pub enum MoveType {
     },
 }
 
+// the king can be used to recapture, but losing it outweighs any trade
+const KING_VALUE: i32 = 20_000;
+
+// least valuable attackers are used first in exchanges
+const EXCHANGE_ORDER: [Id; 6] = [
+    Id::Pawn,
+    Id::Knight,
+    Id::Bishop,
+    Id::Rook,
+    Id::Queen,
+    Id::King,
+];
+
+impl MoveType {
+    /// Type of the piece the move takes, including pawns taken en passant
+    pub fn captured(&self, board: &Board) -> Option<Id> {
+        match self {
+            MoveType::Normal { target, .. } => board.grid[target.y][target.x]
+                .as_ref()
+                .map(|piece| piece.id.clone()),
+            MoveType::EnPassant { .. } => Some(Id::Pawn),
+            MoveType::Castle { .. } => None,
+        }
+    }
+}
+
+/// Static exchange evaluation of a move, in centipawns
+/// * both sides take turns capturing on the target square with their least valuable attacker,
+///   and either side can stop once carrying on would lose material
+/// * pieces behind an attacker join in once it has captured, e.g. a rook behind a queen
+/// * castling is never an exchange, so it is worth 0
+pub fn see(board: &Board, move_type: &MoveType) -> i32 {
+    let value = |id: &Id| match id {
+        Id::King => KING_VALUE,
+        _ => id.value(),
+    };
+
+    let (from, target, moved, white) = match move_type {
+        MoveType::Normal {
+            piece,
+            target,
+            promotion,
+        } => {
+            let moved = match promotion.map(Id::from_char) {
+                Some(Ok(id)) => id,
+                _ => piece.id.clone(),
+            };
+            (piece.position, *target, moved, piece.white)
+        }
+        MoveType::EnPassant { from, target, .. } => {
+            let white = match &board.grid[from.y][from.x] {
+                Some(piece) => piece.white,
+                None => return 0,
+            };
+            (*from, *target, Id::Pawn, white)
+        }
+        MoveType::Castle { .. } => return 0,
+    };
+
+    let square = bitboard::square(target.x, target.y);
+    let mut occupied = board.bitboards.occupied() & !bitboard::bit(from.x, from.y);
+    if let MoveType::EnPassant { capture, .. } = move_type {
+        occupied &= !bitboard::bit(capture.x, capture.y);
+    }
+
+    // promotions gain the difference between the pawn and the new piece
+    let captured = match move_type.captured(board) {
+        Some(id) => value(&id),
+        None => 0,
+    };
+    let promoted = match &moved {
+        Id::Pawn => 0,
+        _ => match move_type {
+            MoveType::Normal { piece, .. } => value(&moved) - value(&piece.id),
+            _ => 0,
+        },
+    };
+
+    // gains[i] is what the side making the ith capture gains if the exchange stops after it
+    let mut gains = vec![captured + promoted];
+    let mut on_square = value(&moved);
+    let mut white = !white;
+    loop {
+        // pieces that have already captured are left out, which uncovers the ones behind them
+        let attacker = EXCHANGE_ORDER.iter().find_map(|id| {
+            let attackers = attacks::piece(id, !white, square, occupied)
+                & board.bitboards.get(id, white)
+                & occupied;
+            match attackers {
+                0 => None,
+                attackers => Some((id, attackers.trailing_zeros() as usize)),
+            }
+        });
+        let (id, from) = match attacker {
+            Some(attacker) => attacker,
+            None => break,
+        };
+
+        gains.push(on_square - gains[gains.len() - 1]);
+        occupied &= !(1 << from);
+        on_square = value(id);
+        white = !white;
+    }
+
+    // each side only captures if it comes out ahead
+    while gains.len() > 1 {
+        let gain = gains.pop().unwrap();
+        let previous = gains.len() - 1;
+        gains[previous] = -(-gains[previous]).max(gain);
+    }
+
+    gains[0]
+}
+
+/// Everything needed to take a move back
+/// * `moved` - the piece that moved, as it was before moving, or the king when castling
+/// * `rook` - the rook that moved when castling
+/// * `captured` - the piece that was taken, including pawns taken en passant
+/// * `state` - castling rights, en passant square and move counters before the move
+/// * `san` and `positions` are the game record before the move
+#[derive(Clone)]
+pub struct MoveRecord {
+    pub move_type: MoveType,
+    pub white: bool,
+    pub moved: Piece,
+    pub rook: Option<Piece>,
+    pub captured: Option<Piece>,
+    pub state: State,
+    pub san: String,
+    pub positions: usize,
+}
+
 pub enum MoveChecker {
     Bishop,
     King,
@@ -42,39 +178,8 @@ impl MoveChecker {
         }
     }
 
-    /// Checks if any pieces blocking along the 2 coorindates
-    fn blocked(board: &Board, from: &Coordinate, to: &Coordinate, white: bool) -> bool {
-        // convert to signed so dx and dy can take negative values
-        let mut x1 = from.x as i8;
-        let mut y1 = from.y as i8;
-        let x2 = to.x as i8;
-        let y2 = to.y as i8;
-
-        // normalise dx and dy to be 1, 0 or -1
-        let dx = (x2 - x1).signum();
-        let dy = (y2 - y1).signum();
-
-        while x1 != x2 || y1 != y2 {
-            x1 += dx;
-            y1 += dy;
-
-            match &board.grid[y1 as usize][x1 as usize] {
-                Some(piece) => {
-                    // if reached target position,
-                    // check if it's a capture or it's blocked
-                    if x1 == x2 && y1 == y2 {
-                        return piece.white == white;
-                    }
-
-                    return true;
-                }
-                None => (),
-            }
-        }
-        return false;
-    }
-
     /// Checks if the target position is within the board
+    #[allow(clippy::needless_return)]
     fn in_bounds(target: &Coordinate) -> bool {
         // don't need to check negative since x and y are unsigned
         return target.x < NUM_COLS && target.y < NUM_ROWS;
@@ -87,179 +192,39 @@ impl MoveChecker {
         }
 
         // check if the target position contains a friendly piece
-        match &board.grid[target.y][target.x] {
-            Some(target) => {
-                if piece.white == target.white {
-                    return false;
-                }
+        if let Some(target) = &board.grid[target.y][target.x] {
+            if piece.white == target.white {
+                return false;
             }
-            None => (),
         }
 
-        // unsigned distance along x and y axes
-        let dx = piece.position.x.abs_diff(target.x);
-        let dy = piece.position.y.abs_diff(target.y);
-
-        // check if the move is actually a move
-        if dx == 0 && dy == 0 {
-            return false;
-        }
-
-        match self {
-            Self::Bishop => {
-                // check if diagonal
-                if !(dx == dy) {
-                    return false;
-                }
-
-                return !Self::blocked(board, &piece.position, target, piece.white);
-            }
-            Self::King => {
-                // 1 squre in cardinal and ordinal directions
-                if !((dx + dy) == 1 || (dx == 1 && dy == 1)) {
-                    return false;
-                }
-
-                return !Self::blocked(board, &piece.position, target, piece.white);
-            }
-            Self::Knight => {
-                // L-shape, don't need to check if blocked
-                return (dx == 1 && dy == 2) || (dx == 2 && dy == 1);
-            }
+        // sliding pieces stop at the first piece in the way
+        let from = bitboard::square(piece.position.x, piece.position.y);
+        let occupied = board.bitboards.occupied();
+        let targets = match self {
+            Self::Bishop => attacks::bishop(from, occupied),
+            Self::King => attacks::king(from),
+            Self::Knight => attacks::knight(from),
             Self::Pawn => {
-                // check if the pawn is moving in the correct direction,
-                // and whether the position is within range
-                if (target.y > piece.position.y) != piece.white || dy > 2 {
-                    return false;
-                }
-
-                // if diagonal, check if a piece exists there
-                if dx != 0 {
-                    // check if the distance is correct
-                    if dx != 1 || dy != 1 {
-                        return false;
-                    }
-
-                    // can only move diagonal when taking
-                    return match &board.grid[target.y][target.x] {
-                        Some(_) => true,
-                        None => false,
-                    };
-                }
-
-                // if the piece has not moved, can move 2
-                let starting_rank = if piece.white { 1 } else { NUM_ROWS - 2 };
-                if dy == 2 && piece.position.y != starting_rank {
-                    return false;
-                }
-
-                // check if a piece occupies the target position
-                // this requires a different check because pawns can only capture along a diagonal
-                match &board.grid[target.y][target.x] {
-                    Some(_) => return false,
-                    None => (),
-                }
-
-                return !Self::blocked(board, &piece.position, target, piece.white);
-            }
-            Self::Queen => {
-                // check if diagonal or vertical or horizontal
-                if !(dx == dy || dx == 0 || dy == 0) {
-                    return false;
-                }
-
-                return !Self::blocked(board, &piece.position, target, piece.white);
-            }
-            Self::Rook => {
-                // check if vertical or horizontal
-                if !(dx == 0 || dy == 0) {
-                    return false;
-                }
-
-                return !Self::blocked(board, &piece.position, target, piece.white);
+                // can only move diagonal when taking
+                bitboard::pawn_pushes(piece.white, from, occupied)
+                    | (attacks::pawn(piece.white, from) & occupied)
             }
+            Self::Queen => attacks::queen(from, occupied),
+            Self::Rook => attacks::rook(from, occupied),
         };
+
+        targets & bitboard::bit(target.x, target.y) != 0
     }
 
     /// Checks if the king is in check
     pub fn in_check(board: &Board, white: bool) -> bool {
-        let mut position: Option<&Coordinate> = None;
-        for row in &board.grid {
-            for piece in row {
-                match piece {
-                    Some(piece) => {
-                        // find the king
-                        if piece.white == white && piece.id == Id::King {
-                            position = Some(&piece.position);
-                        }
-                    }
-                    None => continue,
-                }
-            }
-        }
-
-        // there might not be a king for custom boards, so just return false
-        let position = match position {
-            Some(position) => position,
-            None => return false,
-        };
-
-        for row in &board.grid {
-            for piece in row {
-                match piece {
-                    // check if any pieces can attack the king
-                    Some(piece) => {
-                        if piece.white != white {
-                            let checker = MoveChecker::from_id(&piece.id);
-                            if checker.can_move(&board, &piece, &position) {
-                                return true;
-                            }
-                        }
-                    }
-                    None => continue,
-                }
-            }
-        }
-
-        return false;
+        board.bitboards.in_check(white)
     }
 
-    /// Checks if there are any moves that can be made
-    pub fn checkmate(board: &Board, white: bool) -> bool {
-        if !Self::in_check(board, white) {
-            return false;
-        }
-
-        // check if any moves are available
-        // cannot castle while in check
-        for row in &board.grid {
-            for piece in row {
-                match piece {
-                    Some(piece) => {
-                        if piece.white == white {
-                            let letter = piece.id.to_char();
-                            for y in 0..NUM_ROWS {
-                                for x in 0..NUM_COLS {
-                                    let target = format!("{}{}", (x as u8) as char, y.to_string());
-                                    let input = match letter {
-                                        Some(letter) => format!("{}{}", letter, target),
-                                        None => target,
-                                    };
-
-                                    match board.parse_move(&input, white) {
-                                        Ok(_) => return false,
-                                        Err(_) => continue,
-                                    }
-                                }
-                            }
-                        }
-                    }
-                    None => continue,
-                }
-            }
-        }
-
-        return true;
+    /// Checks if the side to move is in check with no moves that can be made
+    pub fn checkmate(board: &Board) -> bool {
+        Self::in_check(board, board.state.white) && board.legal_moves().is_empty()
     }
 
     /// Checks if castling is possible
@@ -281,22 +246,20 @@ impl MoveChecker {
             }
         }
 
-        if king.is_none() {
-            return None;
-        }
-
-        let king = king.unwrap();
+        let king = king?;
         let range = match kingside {
             true => (king.position.x + 1)..NUM_COLS,
             false => 0..king.position.x,
         };
 
+        // can only castle with a rook that still has the right to
+        let files = board.state.castling_files(white);
         let mut rook: Option<&Piece> = None;
         for i in range {
             match &board.grid[rank][i] {
                 Some(piece) => {
                     // find the rook
-                    if piece.id == Id::Rook && piece.white == white {
+                    if piece.id == Id::Rook && piece.white == white && files.contains(&i) {
                         rook = Some(piece);
                         break;
                     }
@@ -305,15 +268,7 @@ impl MoveChecker {
             }
         }
 
-        if rook.is_none() {
-            return None;
-        }
-
-        // can only castle if both pieces haven't moved
-        let rook = rook.unwrap();
-        if king.moves != 0 || rook.moves != 0 {
-            return None;
-        }
+        let rook = rook?;
 
         // castled king and rook positions are always the same
         let files = match kingside {
@@ -324,36 +279,36 @@ impl MoveChecker {
         let rook_target = files[1];
 
         // check if any pieces are in the way
-        let files = vec![king.position.x, rook.position.x, king_target, rook_target];
+        let files = [king.position.x, rook.position.x, king_target, rook_target];
 
         // get left and right bounds
         // it should be safe to unwrap here
-        let left = files.iter().min().unwrap().clone();
-        let right = files.iter().max().unwrap().clone();
+        let left = *files.iter().min().unwrap();
+        let right = *files.iter().max().unwrap();
         for i in left..=right {
             if i == king.position.x || i == rook.position.x {
                 continue;
             }
 
-            match &board.grid[rank][i] {
-                Some(_) => return None,
-                None => (),
-            };
+            if board.grid[rank][i].is_some() {
+                return None;
+            }
         }
 
         // check if any squares in the king's path are under attack
         let left = king.position.x.min(king_target);
         let right = king.position.x.max(king_target);
+        let mut bitboards = board.bitboards;
+        bitboards.clear(bitboard::square(king.position.x, rank));
         for i in left..=right {
-            let mut test_board = board.clone();
-            test_board.grid[rank][king.position.x] = None;
-            test_board.place_piece(i, rank, king.icon, white, king.moves);
-            if Self::in_check(&test_board, white) {
+            let mut test = bitboards;
+            test.set(bitboard::square(i, rank), &Id::King, white);
+            if test.in_check(white) {
                 return None;
             }
         }
 
-        return Some((king.position.x, rook.position.x));
+        Some((king.position.x, rook.position.x))
     }
 
     /// Google en passant
@@ -370,9 +325,8 @@ impl MoveChecker {
         }
 
         // check if the target position is occupied
-        match &board.grid[target.y][target.x] {
-            Some(_) => return None,
-            None => (),
+        if board.grid[target.y][target.x].is_some() {
+            return None;
         }
 
         // en passant can only occur when the pawn moves 2 squares,
@@ -386,64 +340,35 @@ impl MoveChecker {
             y: rank,
         };
 
-        // check if the last move was a long pawn move
-        match board.history.last() {
-            Some(input) => {
-                let last_move = Coordinate::from_alphanumeric(input);
-                match last_move {
-                    Ok(last_move) => {
-                        // check that the last move targets the en passant square
-                        if last_move.x != square.x || last_move.y != square.y {
-                            return None;
-                        }
-                    }
-                    // error will be thrown if it isn't a pawn move
-                    Err(_) => return None,
-                }
-            }
-            None => return None,
-        };
+        // check that the last move was a long pawn move over the target
+        if board.state.en_passant != Some(*target) {
+            return None;
+        }
 
+        // check the piece to capture is a pawn
         match &board.grid[square.y][square.x] {
-            Some(piece) => {
-                // check the piece to capture is a pawn
-                if piece.id == Id::Pawn && piece.moves == 1 {
-                    // check if there is a pawn to do the en passant
-                    match from {
-                        Some(x) => match &board.grid[rank][x] {
-                            Some(piece) => return Some((piece.position.x, piece.position.y)),
-                            None => return None,
-                        },
-                        // if no file specified, check if any are valid
-                        None => {
-                            if square.x > 0 {
-                                let left = square.x - 1;
-                                match &board.grid[rank][left] {
-                                    Some(piece) => {
-                                        return Some((piece.position.x, piece.position.y))
-                                    }
-                                    None => (),
-                                }
-                            }
-
-                            if square.x < NUM_COLS - 1 {
-                                let right = square.x + 1;
-                                match &board.grid[rank][right] {
-                                    Some(piece) => {
-                                        return Some((piece.position.x, piece.position.y))
-                                    }
-                                    None => (),
-                                }
-                            }
-
-                            return None;
-                        }
-                    };
+            Some(piece) if piece.id == Id::Pawn && piece.white != white => (),
+            _ => return None,
+        }
+
+        // the capturing piece must be one of the side's own pawns, next to the captured pawn
+        let capturer = |x: usize| -> Option<(usize, usize)> {
+            if x >= NUM_COLS || x.abs_diff(square.x) != 1 {
+                return None;
+            }
+
+            match &board.grid[rank][x] {
+                Some(piece) if piece.id == Id::Pawn && piece.white == white => {
+                    Some((piece.position.x, piece.position.y))
                 }
+                _ => None,
             }
-            None => (),
         };
 
-        return None;
+        // if no file specified, check if any are valid
+        match from {
+            Some(x) => capturer(x),
+            None => capturer(square.x.wrapping_sub(1)).or_else(|| capturer(square.x + 1)),
+        }
     }
 }
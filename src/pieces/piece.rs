@@ -1,7 +1,7 @@
 use crate::coordinate::Coordinate;
 use crate::Error;
 
-#[derive(Clone, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 pub enum Id {
     Bishop,
     King,
@@ -20,7 +20,7 @@ impl Id {
             'P' | '♙' => Ok(Self::Pawn),
             'Q' | '♕' => Ok(Self::Queen),
             'R' | '♖' => Ok(Self::Rook),
-            _ => Err(Error::InvalidArgument),
+            _ => Err(Error::UnknownPiece { letter: icon }),
         }
     }
 
@@ -34,6 +34,55 @@ impl Id {
             Self::Rook => Some('R'),
         }
     }
+
+    /// Position in tables indexed by piece type, from pawn to king
+    pub fn index(&self) -> usize {
+        match self {
+            Self::Pawn => 0,
+            Self::Knight => 1,
+            Self::Bishop => 2,
+            Self::Rook => 3,
+            Self::Queen => 4,
+            Self::King => 5,
+        }
+    }
+
+    /// Rough value in centipawns, for ordering and trading captures
+    /// * kings can't be traded, so they are worth nothing
+    pub fn value(&self) -> i32 {
+        match self {
+            Self::Pawn => 100,
+            Self::Knight => 320,
+            Self::Bishop => 330,
+            Self::Rook => 500,
+            Self::Queen => 900,
+            Self::King => 0,
+        }
+    }
+
+    /// Lowercase name, for messages
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Bishop => "bishop",
+            Self::King => "king",
+            Self::Knight => "knight",
+            Self::Pawn => "pawn",
+            Self::Queen => "queen",
+            Self::Rook => "rook",
+        }
+    }
+
+    /// Unicode icon used on the board, shared by both colours
+    pub fn to_icon(&self) -> char {
+        match self {
+            Self::Bishop => '♗',
+            Self::King => '♔',
+            Self::Knight => '♘',
+            Self::Pawn => '♙',
+            Self::Queen => '♕',
+            Self::Rook => '♖',
+        }
+    }
 }
 
 /// Basic properties for each piece
@@ -41,8 +90,8 @@ impl Id {
 /// * `id` - [Id]
 /// * `icon` - unicode with combining characters
 /// * `white` - `true` for white, `false` for black
-/// * `moves` - track number of moves for castling and en passant checks
-#[derive(Clone)]
+/// * `moves` - number of times the piece has moved
+#[derive(Clone, PartialEq)]
 pub struct Piece {
     pub position: Coordinate,
     pub id: Id,
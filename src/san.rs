@@ -0,0 +1,117 @@
+use crate::board::Board;
+use crate::pieces::moves::MoveType;
+use crate::pieces::{Id, MoveChecker, Piece};
+
+impl Board {
+    /// Writes a move in standard algebraic notation
+    /// * only disambiguates as much as needed, preferring the file over the rank
+    /// * captures are marked with `x` and promotions with `=`, e.g. `exd8=Q`
+    /// * ends with `+` for check or `#` for checkmate
+    /// * `move_type` must be legal for the side to move
+    pub fn to_san(&self, move_type: &MoveType) -> String {
+        let white = self.state.white;
+        let mut san = match move_type {
+            MoveType::Normal {
+                piece,
+                target,
+                promotion,
+            } => {
+                let capture = self.grid[target.y][target.x].is_some();
+                let mut san = String::new();
+
+                match piece.id.to_char() {
+                    Some(letter) => {
+                        san.push(letter);
+                        san.push_str(&self.disambiguation(move_type, piece));
+                    }
+                    None if capture => san.push(Self::file(piece.position.x)),
+                    None => (),
+                }
+
+                if capture {
+                    san.push('x');
+                }
+                san.push_str(&target.to_alphanumeric());
+
+                if let Some(Ok(id)) = promotion.map(Id::from_char) {
+                    san.push('=');
+                    san.push(id.to_char().unwrap_or('Q'));
+                }
+
+                san
+            }
+            MoveType::Castle { kingside, .. } => match kingside {
+                true => String::from("O-O"),
+                false => String::from("O-O-O"),
+            },
+            MoveType::EnPassant { from, target, .. } => {
+                format!("{}x{}", Self::file(from.x), target.to_alphanumeric())
+            }
+        };
+
+        let mut test_board = self.position();
+        test_board.apply_move(move_type, white);
+        if MoveChecker::in_check(&test_board, !white) {
+            match test_board.legal_moves().is_empty() {
+                true => san.push('#'),
+                false => san.push('+'),
+            }
+        }
+
+        san
+    }
+
+    /// Finds the file, rank or square needed to tell apart pieces of the same type
+    /// * returns an empty string if no other piece of that type can reach the target
+    fn disambiguation(&self, move_type: &MoveType, piece: &Piece) -> String {
+        let target = match move_type {
+            MoveType::Normal { target, .. } => target,
+            _ => return String::new(),
+        };
+
+        let mut others: Vec<Piece> = Vec::new();
+        for other in self.legal_moves() {
+            if let MoveType::Normal {
+                piece: other,
+                target: other_target,
+                ..
+            } = other
+            {
+                if other.id == piece.id
+                    && other_target.x == target.x
+                    && other_target.y == target.y
+                    && (other.position.x != piece.position.x
+                        || other.position.y != piece.position.y)
+                {
+                    others.push(other);
+                }
+            }
+        }
+
+        if others.is_empty() {
+            return String::new();
+        }
+
+        let file = Self::file(piece.position.x);
+        let rank = (piece.position.y + 1).to_string();
+        if others
+            .iter()
+            .all(|other| other.position.x != piece.position.x)
+        {
+            return file.to_string();
+        }
+
+        if others
+            .iter()
+            .all(|other| other.position.y != piece.position.y)
+        {
+            return rank;
+        }
+
+        format!("{}{}", file, rank)
+    }
+
+    fn file(x: usize) -> char {
+        (x as u8 + b'a') as char
+    }
+}
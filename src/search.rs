@@ -0,0 +1,478 @@
+use crate::board::Board;
+use crate::endgame::{Dtm, Endgames};
+use crate::eval::evaluate;
+use crate::pieces::moves::see;
+use crate::pieces::moves::MoveType;
+use crate::pieces::{Id, MoveChecker};
+use crate::transposition::{self, Bound, TranspositionTable};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Score for delivering checkmate, reduced by the number of plies it takes
+pub const MATE: i32 = 100_000;
+const INFINITY: i32 = MATE + 1;
+
+/// Scores beyond this are mates rather than material
+pub const MATE_THRESHOLD: i32 = MATE - 1000;
+
+const MAX_DEPTH: usize = 64;
+
+// margin for positional gains when deciding if a capture could raise alpha, in centipawns
+const DELTA_MARGIN: i32 = 200;
+
+// how often to check the clock, in nodes
+const CHECK_INTERVAL: u64 = 256;
+
+/// When to stop searching
+/// * `depth` - deepest iteration to complete, in plies
+/// * `time` - time to spend before returning the best move so far
+/// * searches until stopped if neither is given
+#[derive(Clone, Default)]
+pub struct Limits {
+    pub depth: Option<usize>,
+    pub time: Option<Duration>,
+}
+
+/// Outcome of the deepest completed iteration
+/// * `best_move` - `None` if there are no legal moves
+/// * `score` - in centipawns for the side to move, or [MATE] minus the plies to mate
+/// * `pv` - principal variation, the moves both sides are expected to play
+/// * `depth` - plies searched
+/// * `nodes` - positions visited across all iterations
+/// * `time` - time taken so far
+#[derive(Clone)]
+pub struct SearchResult {
+    pub best_move: Option<MoveType>,
+    pub score: i32,
+    pub pv: Vec<MoveType>,
+    pub depth: usize,
+    pub nodes: u64,
+    pub time: Duration,
+}
+
+impl SearchResult {
+    /// Number of moves until mate, negative if the side to move is getting mated
+    pub fn mate_in(&self) -> Option<i32> {
+        if self.score.abs() < MATE_THRESHOLD {
+            return None;
+        }
+
+        let plies = MATE - self.score.abs();
+        let moves = (plies + 1) / 2;
+        Some(if self.score > 0 { moves } else { -moves })
+    }
+}
+
+/// Negamax search with alpha-beta pruning and iterative deepening
+/// * `stop` - can be set from another thread to end the search early
+/// * `table` - positions already searched, which can be kept between searches
+/// * `endgames` - generated distance to mate tables, which give perfect play where they apply
+pub struct Search {
+    pub limits: Limits,
+    pub stop: Arc<AtomicBool>,
+    pub table: Arc<Mutex<TranspositionTable>>,
+    pub endgames: Option<Arc<Endgames>>,
+    start: Instant,
+    nodes: u64,
+}
+
+impl Search {
+    pub fn new(limits: Limits) -> Search {
+        let table = TranspositionTable::new(transposition::DEFAULT_SIZE);
+        Self::with_table(limits, Arc::new(Mutex::new(table)))
+    }
+
+    /// Searches with a table shared with earlier searches
+    pub fn with_table(limits: Limits, table: Arc<Mutex<TranspositionTable>>) -> Search {
+        Search {
+            limits,
+            stop: Arc::new(AtomicBool::new(false)),
+            table,
+            endgames: None,
+            start: Instant::now(),
+            nodes: 0,
+        }
+    }
+
+    /// Searches one depth deeper each iteration until a limit is reached, for the side to move
+    /// * `report` - called after each completed iteration
+    pub fn run(&mut self, board: &Board, mut report: impl FnMut(&SearchResult)) -> SearchResult {
+        let white = board.state.white;
+        self.start = Instant::now();
+        self.nodes = 0;
+
+        let root = board.position();
+        let board = &root;
+
+        // only one search runs at a time, so the table is held until it is done
+        let shared = self.table.clone();
+        let mut table = match shared.lock() {
+            Ok(table) => table,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        table.new_search();
+
+        let moves = board.legal_moves();
+
+        let mut result = SearchResult {
+            best_move: moves.first().cloned(),
+            score: 0,
+            pv: Vec::new(),
+            depth: 0,
+            nodes: 0,
+            time: Duration::ZERO,
+        };
+
+        // nothing to think about with one move or none
+        if moves.len() <= 1 {
+            return result;
+        }
+
+        // the endgame tables already know the best move
+        if let Some((move_type, dtm)) = self.endgame_move(board, &moves, white) {
+            result.best_move = Some(move_type.clone());
+            result.score = dtm_score(dtm, 0);
+            result.pv = vec![move_type];
+            result.time = self.elapsed();
+            report(&result);
+            return result;
+        }
+
+        let max_depth = self.limits.depth.unwrap_or(MAX_DEPTH).min(MAX_DEPTH);
+        for depth in 1..=max_depth {
+            let mut pv: Vec<MoveType> = result.pv.clone();
+            let score = self.negamax(
+                &mut table, board, white, depth, 0, -INFINITY, INFINITY, &mut pv,
+            );
+
+            // an unfinished iteration can't be trusted
+            if self.stopped() {
+                break;
+            }
+
+            result = SearchResult {
+                best_move: pv.first().cloned(),
+                score,
+                pv,
+                depth,
+                nodes: self.nodes,
+                time: self.elapsed(),
+            };
+            report(&result);
+
+            // no point looking further once a forced mate is found
+            if score.abs() >= MATE_THRESHOLD {
+                break;
+            }
+
+            // the next iteration usually takes longer than all the ones before it
+            match self.limits.time {
+                Some(time) if self.elapsed() * 2 > time => break,
+                _ => (),
+            }
+        }
+
+        result.nodes = self.nodes;
+        result.time = self.elapsed();
+        result
+    }
+
+    /// Time since the search started
+    pub fn elapsed(&self) -> Duration {
+        self.start.elapsed()
+    }
+
+    fn stopped(&mut self) -> bool {
+        if self.stop.load(Ordering::Relaxed) {
+            return true;
+        }
+
+        match self.limits.time {
+            Some(time) if self.nodes.is_multiple_of(CHECK_INTERVAL) && self.elapsed() >= time => {
+                self.stop.store(true, Ordering::Relaxed);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Scores the position for the side to move
+    /// * `pv` - the expected line, which is searched first and replaced with the new best line
+    #[allow(clippy::too_many_arguments)]
+    fn negamax(
+        &mut self,
+        table: &mut TranspositionTable,
+        board: &Board,
+        white: bool,
+        depth: usize,
+        ply: usize,
+        mut alpha: i32,
+        beta: i32,
+        pv: &mut Vec<MoveType>,
+    ) -> i32 {
+        self.nodes += 1;
+
+        let mut moves = board.legal_moves();
+        if moves.is_empty() {
+            pv.clear();
+            return match MoveChecker::in_check(board, white) {
+                true => -MATE + ply as i32,
+                false => 0,
+            };
+        }
+
+        // captures are played out so the position is only evaluated once it is quiet
+        if depth == 0 {
+            pv.clear();
+            return self.quiescence(board, white, ply, alpha, beta, moves);
+        }
+
+        // a deep enough result from another move order can be used as it is, except at the root
+        // where a move is needed
+        let original_alpha = alpha;
+        let entry = table.probe(board.hash).cloned();
+        match &entry {
+            Some(entry) if ply > 0 && entry.depth >= depth => {
+                let score = transposition::from_table(entry.score, ply);
+                let cutoff = match entry.bound {
+                    Bound::Exact => true,
+                    Bound::Lower => score >= beta,
+                    Bound::Upper => score <= alpha,
+                };
+                if cutoff {
+                    *pv = entry.best_move.iter().cloned().collect();
+                    return score;
+                }
+            }
+            _ => (),
+        }
+
+        // endgames in the tables don't need searching
+        if ply > 0 {
+            if let Some(score) = self.probe(board, ply) {
+                pv.clear();
+                return score;
+            }
+        }
+
+        let expected = match pv.first() {
+            Some(expected) => Some(expected),
+            None => entry.as_ref().and_then(|entry| entry.best_move.as_ref()),
+        };
+        order_moves(board, &mut moves, expected);
+
+        let mut best = -INFINITY;
+        let mut line: Vec<MoveType> = Vec::new();
+        for (i, move_type) in moves.iter().enumerate() {
+            let child = child(board, move_type, white);
+
+            // only the first move follows the expected line
+            let mut child_pv = match i {
+                0 if pv.len() > 1 => pv[1..].to_vec(),
+                _ => Vec::new(),
+            };
+            let score = -self.negamax(
+                table,
+                &child,
+                !white,
+                depth - 1,
+                ply + 1,
+                -beta,
+                -alpha,
+                &mut child_pv,
+            );
+
+            if self.stopped() {
+                return 0;
+            }
+
+            if score > best {
+                best = score;
+                line = vec![move_type.clone()];
+                line.extend(child_pv);
+            }
+
+            alpha = alpha.max(score);
+            if alpha >= beta {
+                break;
+            }
+        }
+
+        let bound = if best >= beta {
+            Bound::Lower
+        } else if best <= original_alpha {
+            Bound::Upper
+        } else {
+            Bound::Exact
+        };
+        let best_move = match bound {
+            Bound::Upper => None,
+            _ => line.first().cloned(),
+        };
+        table.store(board.hash, best_move, depth, bound, best, ply);
+
+        *pv = line;
+        best
+    }
+
+    /// Scores a position from the endgame tables, preferring mates that come sooner
+    fn probe(&self, board: &Board, ply: usize) -> Option<i32> {
+        let endgames = self.endgames.as_ref()?;
+        endgames.probe(board).map(|dtm| dtm_score(dtm, ply))
+    }
+
+    /// Move with the best distance to mate from the endgame tables
+    /// * returns `None` if the position or any position after it isn't in the tables
+    fn endgame_move(
+        &self,
+        board: &Board,
+        moves: &[MoveType],
+        white: bool,
+    ) -> Option<(MoveType, Dtm)> {
+        let endgames = self.endgames.as_ref()?;
+        endgames.probe(board)?;
+
+        let mut best: Option<(MoveType, Dtm)> = None;
+        for move_type in moves {
+            let dtm = endgames.probe(&child(board, move_type, white))?.before();
+            if best.as_ref().is_none_or(|(_, best)| dtm.better(best)) {
+                best = Some((move_type.clone(), dtm));
+            }
+        }
+        best
+    }
+
+    /// Searches captures until the position is quiet, so a capture that can be answered with a
+    /// recapture isn't mistaken for winning material
+    /// * `moves` - legal moves, already generated by the caller
+    /// * the side to move can stand pat on the evaluation instead of capturing, unless in check
+    fn quiescence(
+        &mut self,
+        board: &Board,
+        white: bool,
+        ply: usize,
+        mut alpha: i32,
+        beta: i32,
+        mut moves: Vec<MoveType>,
+    ) -> i32 {
+        let in_check = MoveChecker::in_check(board, white);
+        if moves.is_empty() {
+            return match in_check {
+                true => -MATE + ply as i32,
+                false => 0,
+            };
+        }
+
+        // every way out of check has to be looked at
+        let mut best = -INFINITY;
+        let stand_pat = evaluate(board, white);
+        if !in_check {
+            if stand_pat >= beta {
+                return stand_pat;
+            }
+
+            // even taking a queen wouldn't be enough
+            if stand_pat + Id::Queen.value() + DELTA_MARGIN < alpha {
+                return stand_pat;
+            }
+
+            best = stand_pat;
+            alpha = alpha.max(stand_pat);
+            moves.retain(|move_type| {
+                let gain = gain(board, move_type);
+
+                // only captures that could raise alpha without losing material
+                gain > 0 && stand_pat + gain + DELTA_MARGIN >= alpha && see(board, move_type) >= 0
+            });
+        }
+
+        order_moves(board, &mut moves, None);
+        for move_type in moves.iter() {
+            let child = child(board, move_type, white);
+            self.nodes += 1;
+            let replies = child.legal_moves();
+            let score = -self.quiescence(&child, !white, ply + 1, -beta, -alpha, replies);
+
+            if self.stopped() {
+                return 0;
+            }
+
+            best = best.max(score);
+            alpha = alpha.max(score);
+            if alpha >= beta {
+                break;
+            }
+        }
+
+        best
+    }
+}
+
+/// Finds the best move within the limits
+pub fn search(board: &Board, limits: Limits) -> SearchResult {
+    Search::new(limits).run(board, |_| ())
+}
+
+/// Mate score for a distance to mate found `ply` plies from the root
+fn dtm_score(dtm: Dtm, ply: usize) -> i32 {
+    match dtm {
+        Dtm::Win(plies) => MATE - (ply + plies) as i32,
+        Dtm::Loss(plies) => -MATE + (ply + plies) as i32,
+        Dtm::Draw => 0,
+    }
+}
+
+/// Makes a move on a copy of the board, skipping the bookkeeping only needed for the game record
+fn child(board: &Board, move_type: &MoveType, white: bool) -> Board {
+    let mut child = board.clone();
+    child.apply_move(move_type, white);
+    child
+}
+
+/// Tries the expected best move first, then captures of the most valuable pieces, then quiet
+/// moves, and captures that lose material last
+fn order_moves(board: &Board, moves: &mut [MoveType], best: Option<&MoveType>) {
+    moves.sort_by_cached_key(|move_type| {
+        if Some(move_type) == best {
+            return i32::MIN;
+        }
+
+        let gain = gain(board, move_type);
+        if gain == 0 {
+            return 0;
+        }
+
+        // most valuable victim, least valuable attacker
+        match see(board, move_type) {
+            exchange if exchange < 0 => -exchange,
+            _ => -(gain * 10 - moved(move_type).value() / 10),
+        }
+    });
+}
+
+/// Material taken or promoted to by a move, before any recapture
+fn gain(board: &Board, move_type: &MoveType) -> i32 {
+    let captured = match move_type.captured(board) {
+        Some(id) => id.value(),
+        None => 0,
+    };
+    let promotion = match move_type {
+        MoveType::Normal { promotion, .. } => match promotion.map(Id::from_char) {
+            Some(Ok(id)) => id.value() - Id::Pawn.value(),
+            _ => 0,
+        },
+        _ => 0,
+    };
+
+    captured + promotion
+}
+
+/// Type of the piece that moves, or the king when castling
+fn moved(move_type: &MoveType) -> Id {
+    match move_type {
+        MoveType::Normal { piece, .. } => piece.id.clone(),
+        MoveType::Castle { .. } => Id::King,
+        MoveType::EnPassant { .. } => Id::Pawn,
+    }
+}
@@ -0,0 +1,244 @@
+use crate::board::{Board, CHESS960_POSITIONS, NUM_COLS, NUM_ROWS};
+use crate::pieces::Id;
+use crate::Error;
+use rand::Rng;
+
+/// Ways of shuffling the pieces behind the pawns
+/// * `Chess960` - the same back rank for both sides, with the king between the rooks
+/// * `DoubleFischerRandom` - a chess960 back rank for each side, chosen separately
+/// * `Chess2880` - the same back rank for both sides, with the king anywhere
+/// * `Transcendental` - a back rank for each side with the king anywhere, and no castling
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Variant {
+    Chess960,
+    DoubleFischerRandom,
+    Chess2880,
+    Transcendental,
+}
+
+impl Variant {
+    pub const ALL: [Variant; 4] = [
+        Variant::Chess960,
+        Variant::DoubleFischerRandom,
+        Variant::Chess2880,
+        Variant::Transcendental,
+    ];
+
+    /// Name for menus and the PGN `Variant` tag
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Chess960 => "Chess960",
+            Self::DoubleFischerRandom => "Double Fischer Random",
+            Self::Chess2880 => "Chess 2880",
+            Self::Transcendental => "Transcendental",
+        }
+    }
+
+    /// Reads a name given by [Variant::name]
+    pub fn from_name(name: &str) -> Option<Variant> {
+        Self::ALL.into_iter().find(|variant| variant.name() == name)
+    }
+
+    /// Whether each side gets its own back rank
+    pub fn independent(&self) -> bool {
+        matches!(self, Self::DoubleFischerRandom | Self::Transcendental)
+    }
+
+    /// Whether the king has to start between the rooks
+    pub fn king_between_rooks(&self) -> bool {
+        matches!(self, Self::Chess960 | Self::DoubleFischerRandom)
+    }
+
+    /// Whether the kings start with the right to castle
+    pub fn castling(&self) -> bool {
+        *self != Self::Transcendental
+    }
+
+    /// Picks a back rank at random
+    pub fn back_rank(&self, rng: &mut impl Rng) -> [char; NUM_COLS] {
+        let id = rng.gen_range(0..CHESS960_POSITIONS);
+        let king = match self.king_between_rooks() {
+            true => 1,
+            false => rng.gen_range(0..3),
+        };
+        arrange(id, king)
+    }
+
+    /// Checks that one side's back rank follows the rules of the variant
+    /// * `rank` - pieces from the a to h file, as icons or letters like `RNBQKBNR`
+    pub fn validate(&self, rank: &[char; NUM_COLS]) -> Result<(), Error> {
+        let error = |message: &str| Error::InvalidBackRank {
+            value: letters(rank),
+            message: String::from(message),
+        };
+
+        let ids = ids(rank)?;
+        let files = |id: Id| -> Vec<usize> { (0..NUM_COLS).filter(|x| ids[*x] == id).collect() };
+
+        let counts = [
+            (Id::King, 1),
+            (Id::Queen, 1),
+            (Id::Rook, 2),
+            (Id::Bishop, 2),
+            (Id::Knight, 2),
+        ];
+        if counts
+            .into_iter()
+            .any(|(id, count)| files(id).len() != count)
+        {
+            return Err(error(
+                "there should be a king, a queen and 2 each of rooks, bishops and knights",
+            ));
+        }
+
+        // a1 is dark, so the colour of a back rank square only depends on its file
+        let bishops = files(Id::Bishop);
+        if bishops[0] % 2 == bishops[1] % 2 {
+            return Err(error("the bishops should be on opposite colours"));
+        }
+
+        let king = files(Id::King)[0];
+        let rooks = files(Id::Rook);
+        if self.king_between_rooks() && !(rooks[0] < king && king < rooks[1]) {
+            return Err(error("the king should be between the rooks"));
+        }
+
+        Ok(())
+    }
+}
+
+/// Lays out a chess960 back rank from its number
+/// * `id` - 0 to 959, where 518 is the usual starting position; larger numbers wrap around
+pub fn chess960(id: u16) -> [char; NUM_COLS] {
+    arrange(id, 1)
+}
+
+/// Lays out a back rank from a chess960 number and where the king goes
+/// * the remainders of `id` place the light bishop, the dark bishop, the queen and then the knights
+/// * `king` - which of the last 3 empty squares the king takes, with the rooks on the other 2
+fn arrange(id: u16, king: usize) -> [char; NUM_COLS] {
+    const KNIGHTS: [(usize, usize); 10] = [
+        (0, 1),
+        (0, 2),
+        (0, 3),
+        (0, 4),
+        (1, 2),
+        (1, 3),
+        (1, 4),
+        (2, 3),
+        (2, 4),
+        (3, 4),
+    ];
+
+    let mut id = (id % CHESS960_POSITIONS) as usize;
+    let mut rank = [' '; NUM_COLS];
+
+    // bishops go on the b, d, f or h file and the a, c, e or g file
+    rank[2 * (id % 4) + 1] = '♗';
+    id /= 4;
+    rank[2 * (id % 4)] = '♗';
+    id /= 4;
+
+    let empty = |rank: &[char; NUM_COLS]| -> Vec<usize> {
+        (0..NUM_COLS).filter(|x| rank[*x] == ' ').collect()
+    };
+    rank[empty(&rank)[id % 6]] = '♕';
+    id /= 6;
+
+    let (first, second) = KNIGHTS[id];
+    let remaining = empty(&rank);
+    rank[remaining[first]] = '♘';
+    rank[remaining[second]] = '♘';
+
+    for (i, x) in empty(&rank).into_iter().enumerate() {
+        rank[x] = if i == king % 3 { '♔' } else { '♖' };
+    }
+    rank
+}
+
+/// Reads the pieces of a back rank
+fn ids(rank: &[char; NUM_COLS]) -> Result<Vec<Id>, Error> {
+    rank.iter().map(|icon| Id::from_char(*icon)).collect()
+}
+
+/// Writes a back rank with the letters used in FEN, for messages
+fn letters(rank: &[char; NUM_COLS]) -> String {
+    rank.iter()
+        .map(|icon| match Id::from_char(*icon) {
+            Ok(id) => id.to_char().unwrap_or('P'),
+            Err(_) => *icon,
+        })
+        .collect()
+}
+
+impl Board {
+    /// Sets up a board for a variant with random back ranks
+    pub fn new_variant(variant: Variant, rng: &mut impl Rng) -> Board {
+        let white = variant.back_rank(rng);
+        let black = match variant.independent() {
+            true => variant.back_rank(rng),
+            false => white,
+        };
+        debug_assert!(variant.validate(&white).is_ok() && variant.validate(&black).is_ok());
+
+        let mut board = Self::empty();
+        board.place_back_ranks(&white, &black, variant.castling());
+        board
+    }
+
+    /// Sets up a board with the given pieces behind the pawns
+    /// * `white`, `black` - pieces from the a to h file, as icons or letters like `RNBQKBNR`
+    /// * both ranks are checked against the rules of the `variant`
+    pub fn from_back_ranks(
+        white: &[char; NUM_COLS],
+        black: &[char; NUM_COLS],
+        variant: Variant,
+    ) -> Result<Board, Error> {
+        variant.validate(white)?;
+        variant.validate(black)?;
+        if !variant.independent() && ids(white)? != ids(black)? {
+            return Err(Error::InvalidBackRank {
+                value: letters(black),
+                message: format!("both sides should start with {}", letters(white)),
+            });
+        }
+
+        // icons are what the board shows, so letters are swapped for them
+        let icons = |rank: &[char; NUM_COLS]| -> [char; NUM_COLS] {
+            rank.map(|icon| match Id::from_char(icon) {
+                Ok(id) => id.to_icon(),
+                Err(_) => icon,
+            })
+        };
+
+        let mut board = Self::empty();
+        board.place_back_ranks(&icons(white), &icons(black), variant.castling());
+        Ok(board)
+    }
+
+    /// Places the back ranks of an empty board, with the pawns in front of them
+    /// * `castling` - whether the outermost rooks on each side of the king can castle
+    pub(crate) fn place_back_ranks(
+        &mut self,
+        white: &[char; NUM_COLS],
+        black: &[char; NUM_COLS],
+        castling: bool,
+    ) {
+        for (y, pieces) in [(0, white), (NUM_ROWS - 1, black)] {
+            for (x, icon) in pieces.iter().enumerate() {
+                self.place_piece(x, y, *icon, y == 0, 0);
+            }
+        }
+
+        // pawns remain the same
+        for y in [1, NUM_ROWS - 2] {
+            for x in 0..NUM_COLS {
+                self.place_piece(x, y, '♙', y == 1, 0);
+            }
+        }
+
+        if castling {
+            self.grant_castling();
+        }
+    }
+}
@@ -0,0 +1,107 @@
+use crate::board::{Board, NUM_ROWS};
+use crate::coordinate::Coordinate;
+use crate::pieces::moves::MoveType;
+use crate::pieces::Id;
+
+/// Everything about a position that can't be seen from the pieces alone
+/// * `white` - `true` if white is to move
+/// * `castling` - squares of the rooks that can still castle with their king
+/// * `en_passant` - square a pawn just skipped over by moving 2 squares
+/// * `halfmove_clock` - moves since the last capture or pawn move, for the fifty-move rule
+/// * `fullmove_number` - starts at 1 and increases after each of black's moves
+#[derive(Clone, PartialEq)]
+pub struct State {
+    pub white: bool,
+    pub castling: Vec<Coordinate>,
+    pub en_passant: Option<Coordinate>,
+    pub halfmove_clock: usize,
+    pub fullmove_number: usize,
+}
+
+impl Default for State {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl State {
+    /// White to move with no castling rights
+    pub fn new() -> State {
+        State {
+            white: true,
+            castling: Vec::new(),
+            en_passant: None,
+            halfmove_clock: 0,
+            fullmove_number: 1,
+        }
+    }
+
+    /// Files of the rooks one side can still castle with
+    pub fn castling_files(&self, white: bool) -> Vec<usize> {
+        let rank = Self::home_rank(white);
+        self.castling
+            .iter()
+            .filter(|rook| rook.y == rank)
+            .map(|rook| rook.x)
+            .collect()
+    }
+
+    /// Works out the state after a move, from the board before the move is made
+    pub fn after(&self, board: &Board, move_type: &MoveType, white: bool) -> State {
+        let mut state = self.clone();
+        state.white = !white;
+        state.en_passant = None;
+        if !white {
+            state.fullmove_number += 1;
+        }
+
+        // captures and pawn moves are irreversible, so they reset the clock
+        let irreversible = match move_type {
+            MoveType::Normal { piece, target, .. } => {
+                // a rook loses its rights once it moves or is taken
+                state.revoke(&piece.position);
+                state.revoke(target);
+                if piece.id == Id::King {
+                    state.revoke_all(white);
+                }
+
+                if piece.id == Id::Pawn && piece.position.y.abs_diff(target.y) == 2 {
+                    state.en_passant = Some(Coordinate {
+                        x: target.x,
+                        y: (piece.position.y + target.y) / 2,
+                    });
+                }
+
+                piece.id == Id::Pawn || board.grid[target.y][target.x].is_some()
+            }
+            MoveType::Castle { .. } => {
+                state.revoke_all(white);
+                false
+            }
+            MoveType::EnPassant { .. } => true,
+        };
+        state.halfmove_clock = match irreversible {
+            true => 0,
+            false => self.halfmove_clock + 1,
+        };
+
+        state
+    }
+
+    fn revoke(&mut self, square: &Coordinate) {
+        self.castling.retain(|rook| rook != square);
+    }
+
+    fn revoke_all(&mut self, white: bool) {
+        let rank = Self::home_rank(white);
+        self.castling.retain(|rook| rook.y != rank);
+    }
+
+    fn home_rank(white: bool) -> usize {
+        if white {
+            0
+        } else {
+            NUM_ROWS - 1
+        }
+    }
+}
@@ -0,0 +1,95 @@
+use crate::attacks;
+use crate::bitboard::{bit, square, squares, Bitboard};
+use crate::board::{NUM_COLS, NUM_ROWS};
+use crate::pieces::Id;
+
+/// Walks each direction square by square, to check the tables against
+fn slow_slide(x: usize, y: usize, directions: &[(i32, i32)], occupied: Bitboard) -> Bitboard {
+    let mut bitboard = 0;
+    for (dx, dy) in directions {
+        let (mut x, mut y) = (x as i32 + dx, y as i32 + dy);
+        while (0..NUM_COLS as i32).contains(&x) && (0..NUM_ROWS as i32).contains(&y) {
+            bitboard |= bit(x as usize, y as usize);
+            if occupied & bit(x as usize, y as usize) != 0 {
+                break;
+            }
+            x += dx;
+            y += dy;
+        }
+    }
+    bitboard
+}
+
+#[test]
+fn leapers() {
+    assert_eq!(attacks::knight(square(0, 0)), bit(1, 2) | bit(2, 1));
+    assert_eq!(squares(attacks::knight(square(3, 3))).count(), 8);
+    assert_eq!(squares(attacks::king(square(4, 4))).count(), 8);
+    assert_eq!(squares(attacks::king(square(7, 7))).count(), 3);
+
+    // pawns on the edge only attack one way
+    assert_eq!(attacks::pawn(true, square(0, 1)), bit(1, 2));
+    assert_eq!(attacks::pawn(false, square(3, 6)), bit(2, 5) | bit(4, 5));
+}
+
+#[test]
+fn sliders() {
+    // rays stop at the first piece in the way, which can be captured
+    let occupied = bit(3, 5) | bit(6, 3);
+    let rook = attacks::rook(square(3, 3), occupied);
+    assert_eq!(squares(rook).count(), 3 + 2 + 3 + 3);
+    assert!(rook & bit(3, 5) != 0);
+    assert!(rook & bit(3, 6) == 0);
+
+    assert_eq!(squares(attacks::bishop(square(0, 0), 0)).count(), 7);
+    assert_eq!(
+        attacks::queen(square(3, 3), occupied),
+        rook | attacks::bishop(square(3, 3), occupied)
+    );
+}
+
+#[test]
+fn magics_match_slow_rays() {
+    let diagonals = [(1, 1), (1, -1), (-1, 1), (-1, -1)];
+    let straights = [(1, 0), (-1, 0), (0, 1), (0, -1)];
+
+    // a spread of occupancies, from empty to crowded
+    let mut occupied: Bitboard = 0;
+    for i in 0..200u64 {
+        occupied = occupied
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407 + i);
+        let occupied = match i % 3 {
+            0 => occupied & occupied >> 7,
+            1 => occupied,
+            _ => 0,
+        };
+
+        for y in 0..NUM_ROWS {
+            for x in 0..NUM_COLS {
+                let square = square(x, y);
+                assert_eq!(
+                    attacks::bishop(square, occupied),
+                    slow_slide(x, y, &diagonals, occupied)
+                );
+                assert_eq!(
+                    attacks::rook(square, occupied),
+                    slow_slide(x, y, &straights, occupied)
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn by_piece() {
+    let occupied = bit(4, 4);
+    assert_eq!(
+        attacks::piece(&Id::Queen, true, square(0, 0), occupied),
+        attacks::queen(square(0, 0), occupied)
+    );
+    assert_eq!(
+        attacks::piece(&Id::Pawn, false, square(4, 4), occupied),
+        attacks::pawn(false, square(4, 4))
+    );
+}
@@ -0,0 +1,69 @@
+use crate::bitboard::{bit, pawn_pushes, square, Bitboards};
+use crate::board::{Board, NUM_COLS, NUM_ROWS};
+use crate::pieces::Id;
+
+/// Checks that the bitboards hold exactly the pieces in the grid
+fn test_mirrors_grid(board: &Board) {
+    for y in 0..NUM_ROWS {
+        for x in 0..NUM_COLS {
+            let expected = board.grid[y][x]
+                .as_ref()
+                .map(|piece| (piece.id.clone(), piece.white));
+            assert!(board.bitboards.piece_at(square(x, y)) == expected);
+        }
+    }
+}
+
+#[test]
+fn starting_position() {
+    let board = Board::new();
+    test_mirrors_grid(&board);
+    assert_eq!(board.bitboards.colour(true), 0xffff);
+    assert_eq!(board.bitboards.colour(false), 0xffff << 48);
+    assert_eq!(board.bitboards.get(&Id::King, true), bit(4, 0));
+}
+
+#[test]
+fn follows_moves() {
+    let mut board = Board::new();
+    for input in [
+        "e4", "d5", "exd5", "c5", "dxc6", "Nf6", "cxb7", "e6", "bxa8=Q", "Be7", "Nf3", "O-O",
+    ] {
+        assert!(board.make_move(input));
+        test_mirrors_grid(&board);
+    }
+
+    while board.undo_move() {
+        test_mirrors_grid(&board);
+    }
+}
+
+#[test]
+fn pawn_moves() {
+    assert_eq!(pawn_pushes(true, square(4, 1), 0), bit(4, 2) | bit(4, 3));
+    assert_eq!(pawn_pushes(true, square(4, 2), 0), bit(4, 3));
+    assert_eq!(pawn_pushes(true, square(4, 1), bit(4, 3)), bit(4, 2));
+    assert_eq!(pawn_pushes(false, square(4, 6), bit(4, 5)), 0);
+}
+
+#[test]
+fn check() {
+    let mut bitboards = Bitboards::new();
+    bitboards.set(square(4, 0), &Id::King, true);
+    bitboards.set(square(4, 7), &Id::Rook, false);
+    assert!(bitboards.in_check(true));
+
+    bitboards.set(square(4, 3), &Id::Knight, true);
+    assert!(!bitboards.in_check(true));
+    assert!(bitboards.attacked(square(4, 3), false));
+    assert_eq!(bitboards.attackers(square(4, 3), false), bit(4, 7));
+}
+
+#[test]
+fn legal_move_counts() {
+    assert_eq!(Board::new().legal_moves().len(), 20);
+
+    let kiwipete = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1";
+    let board = Board::from_fen(kiwipete).ok().unwrap();
+    assert_eq!(board.legal_moves().len(), 48);
+}
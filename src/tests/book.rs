@@ -0,0 +1,141 @@
+use crate::board::Board;
+use crate::book::{encode, Book, Entry};
+use crate::pgn::Pgn;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+const GAMES: &str = r#"[Event "1"]
+[Result "1-0"]
+
+1. e4 e5 2. Nf3 Nc6 1-0
+
+[Event "2"]
+[Result "1/2-1/2"]
+
+1. e4 c5 2. Nf3 1/2-1/2
+
+[Event "3"]
+[Result "0-1"]
+
+1. d4 d5 0-1
+"#;
+
+fn book() -> Book {
+    Book::build(&Pgn::parse_all(GAMES).ok().unwrap(), 20)
+}
+
+/// Book moves for the position after some moves, in algebraic notation
+fn book_moves(book: &Book, moves: &[&str]) -> Vec<(String, u16)> {
+    let mut board = Board::new();
+    for input in moves {
+        board.make_move(input);
+    }
+
+    book.moves(&board)
+        .iter()
+        .map(|(move_type, weight)| (board.to_san(move_type), *weight))
+        .collect()
+}
+
+#[test]
+fn encoding() {
+    let board = Board::new();
+    let e4 = board.parse_move("e4").ok().unwrap();
+    assert_eq!(encode(&e4, true), 796);
+
+    // castling is the king taking its rook
+    let board = Board::from_fen("r3k2r/1P6/8/8/8/8/8/R3K2R w KQkq - 0 1")
+        .ok()
+        .unwrap();
+    let castle = board.parse_move("O-O").ok().unwrap();
+    assert_eq!(encode(&castle, true), 263);
+    let castle = board.parse_move("O-O").ok().unwrap();
+    assert_eq!(encode(&castle, false), 60 << 6 | 63);
+
+    let promotion = board.parse_move("bxa8=Q").ok().unwrap();
+    assert_eq!(encode(&promotion, true), 4 << 12 | 6 << 9 | 1 << 6 | 56);
+}
+
+#[test]
+fn build() {
+    // wins count for 2 and draws for 1, for the side that played the move
+    let book = book();
+    assert_eq!(book_moves(&book, &[]), vec![(String::from("e4"), 3)]);
+    assert_eq!(book_moves(&book, &["e4"]), vec![(String::from("c5"), 1)]);
+    assert_eq!(book_moves(&book, &["d4"]), vec![(String::from("d5"), 2)]);
+    assert!(book_moves(&book, &["e4", "e5", "Nf3", "Nc6"]).is_empty());
+
+    // plies past the limit are left out
+    let book = Book::build(&Pgn::parse_all(GAMES).ok().unwrap(), 1);
+    assert!(book_moves(&book, &["e4"]).is_empty());
+}
+
+#[test]
+fn bytes() {
+    let book = book();
+    let bytes = book.to_bytes();
+    assert_eq!(bytes.len(), book.entries.len() * 16);
+    assert!(Book::from_bytes(&bytes).ok().unwrap().entries == book.entries);
+    assert!(Book::from_bytes(&bytes[1..]).is_err());
+
+    // big-endian, key first
+    let entry = Entry {
+        key: 0x0102030405060708,
+        raw: 0x090a,
+        weight: 0x0b0c,
+        learn: 0x0d0e0f10,
+    };
+    let book = Book {
+        entries: vec![entry],
+    };
+    assert_eq!(book.to_bytes(), (1..=16).collect::<Vec<u8>>());
+}
+
+#[test]
+fn entries() {
+    let book = Book::from_bytes(&[]).ok().unwrap();
+    assert!(book.entries(Board::new().hash).is_empty());
+
+    let entry = |key: u64, raw: u16| Entry {
+        key,
+        raw,
+        weight: 1,
+        learn: 0,
+    };
+    let book = Book {
+        entries: vec![entry(1, 0), entry(3, 1), entry(3, 2), entry(5, 3)],
+    };
+    assert_eq!(book.entries(3).len(), 2);
+    assert!(book.entries(4).is_empty());
+    assert!(book.entries(6).is_empty());
+}
+
+#[test]
+fn choose() {
+    let book = book();
+    let mut rng = StdRng::seed_from_u64(0);
+    let board = Board::new();
+    for _ in 0..10 {
+        let move_type = book.choose(&board, &mut rng).unwrap();
+        assert_eq!(board.to_san(&move_type), "e4");
+    }
+
+    // every weighted move gets played sometimes
+    let mut board = Board::new();
+    board.make_move("Nf3");
+    let games: Vec<Pgn> = ["1. Nf3 d5 1-0", "1. Nf3 Nf6 0-1", "1. Nf3 c5 0-1"]
+        .iter()
+        .map(|text| Pgn::parse(text).ok().unwrap())
+        .collect();
+    let book = Book::build(&games, 2);
+    let mut seen: Vec<String> = (0..50)
+        .map(|_| board.to_san(&book.choose(&board, &mut rng).unwrap()))
+        .collect();
+    seen.sort();
+    seen.dedup();
+    assert_eq!(seen, vec!["Nf6", "c5"]);
+
+    // out of the book
+    board.make_move("Nf6");
+    assert!(book.choose(&board, &mut rng).is_none());
+}
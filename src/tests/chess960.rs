@@ -0,0 +1,52 @@
+use crate::board::{Board, CHESS960_POSITIONS};
+use crate::fen::STARTING_FEN;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+fn back_rank(id: u16) -> String {
+    let fen = Board::new_chess960(id).to_fen();
+    String::from(fen.split('/').next().unwrap())
+}
+
+#[test]
+fn numbering() {
+    assert_eq!(Board::new_chess960(518).to_fen(), STARTING_FEN);
+    assert_eq!(back_rank(0), "bbqnnrkr");
+    assert_eq!(back_rank(1), "bqnbnrkr");
+    assert_eq!(back_rank(959), "rkrnnqbb");
+    assert_eq!(back_rank(960), back_rank(0));
+}
+
+#[test]
+fn chess960_id() {
+    let mut ranks: Vec<String> = (0..CHESS960_POSITIONS).map(back_rank).collect();
+    ranks.sort();
+    ranks.dedup();
+    assert_eq!(ranks.len(), CHESS960_POSITIONS as usize);
+
+    for id in 0..CHESS960_POSITIONS {
+        assert_eq!(Board::new_chess960(id).chess960_id(), Some(id));
+    }
+
+    // positions that don't come from the start of a game
+    let board = Board::from_fen("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 1")
+        .ok()
+        .unwrap();
+    assert_eq!(board.chess960_id(), Some(518));
+    let board = Board::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBRN w Qq - 0 1")
+        .ok()
+        .unwrap();
+    assert_eq!(board.chess960_id(), None);
+    let board = Board::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQK1NR w KQkq - 0 1")
+        .ok()
+        .unwrap();
+    assert_eq!(board.chess960_id(), None);
+}
+
+#[test]
+fn seeded() {
+    let random = |seed: u64| Board::new_random(&mut StdRng::seed_from_u64(seed)).chess960_id();
+    assert_eq!(random(42), random(42));
+    assert!(random(42).is_some());
+    assert!((0..10).any(|seed| random(seed) != random(42)));
+}
@@ -0,0 +1,182 @@
+use crate::board::Board;
+use crate::clock::{self, Clock, TimeControl};
+use crate::game::Termination;
+use std::time::{Duration, Instant};
+
+fn seconds(seconds: u64) -> Duration {
+    Duration::from_secs(seconds)
+}
+
+/// Plays a move on the clock after thinking for some time
+fn press(clock: &mut Clock, white: bool, now: &mut Instant, thinking: u64) -> bool {
+    *now += seconds(thinking);
+    clock.press_at(white, *now)
+}
+
+#[test]
+fn sudden_death() {
+    let mut clock = Clock::new(TimeControl::SuddenDeath { base: seconds(60) });
+    let mut now = Instant::now();
+    clock.start_at(true, now);
+    assert!(press(&mut clock, true, &mut now, 10));
+    assert_eq!(clock.remaining_at(true, now), seconds(50));
+    assert_eq!(clock.running(), Some(false));
+
+    // only the side to move loses time
+    assert_eq!(clock.remaining_at(true, now + seconds(5)), seconds(50));
+    assert_eq!(clock.remaining_at(false, now + seconds(5)), seconds(55));
+}
+
+#[test]
+fn fischer() {
+    let mut clock = Clock::new(TimeControl::Fischer {
+        base: seconds(60),
+        increment: seconds(2),
+    });
+    let mut now = Instant::now();
+    clock.start_at(true, now);
+    assert!(press(&mut clock, true, &mut now, 10));
+    assert!(press(&mut clock, false, &mut now, 1));
+    assert_eq!(clock.remaining_at(true, now), seconds(52));
+    assert_eq!(clock.remaining_at(false, now), seconds(61));
+}
+
+#[test]
+fn bronstein() {
+    let mut clock = Clock::new(TimeControl::Bronstein {
+        base: seconds(60),
+        delay: seconds(5),
+    });
+    let mut now = Instant::now();
+    clock.start_at(true, now);
+
+    // quick moves cost nothing, but time is never gained
+    assert!(press(&mut clock, true, &mut now, 3));
+    assert!(press(&mut clock, false, &mut now, 10));
+    assert_eq!(clock.remaining_at(true, now), seconds(60));
+    assert_eq!(clock.remaining_at(false, now), seconds(55));
+}
+
+#[test]
+fn moves_per_period() {
+    let mut clock = Clock::new(TimeControl::MovesPerPeriod {
+        base: seconds(60),
+        moves: 2,
+    });
+    let mut now = Instant::now();
+    clock.start_at(true, now);
+    assert!(press(&mut clock, true, &mut now, 10));
+    assert!(press(&mut clock, false, &mut now, 0));
+    assert_eq!(clock.remaining_at(true, now), seconds(50));
+    assert_eq!(
+        clock.allocate(true),
+        clock::allocate(seconds(50), Duration::ZERO, Some(1))
+    );
+
+    assert!(press(&mut clock, true, &mut now, 10));
+    assert_eq!(clock.remaining_at(true, now), seconds(100));
+}
+
+#[test]
+fn flag() {
+    let mut clock = Clock::new(TimeControl::Fischer {
+        base: seconds(10),
+        increment: seconds(5),
+    });
+    let mut now = Instant::now();
+    clock.start_at(true, now);
+
+    // no increment for a move made too late
+    assert!(!press(&mut clock, true, &mut now, 11));
+    assert!(clock.flagged(true));
+    assert!(!clock.flagged(false));
+}
+
+#[test]
+fn stop() {
+    let mut clock = Clock::new(TimeControl::SuddenDeath { base: seconds(60) });
+    let now = Instant::now();
+    clock.start_at(true, now);
+    clock.stop_at(now + seconds(10));
+    assert_eq!(clock.running(), None);
+    assert_eq!(clock.remaining_at(true, now + seconds(20)), seconds(50));
+}
+
+#[test]
+fn timeout() {
+    // the side to move runs out of time
+    let mut board = Board::new();
+    let mut clock = Clock::new(TimeControl::SuddenDeath { base: seconds(1) });
+    clock.start_at(true, Instant::now() - seconds(2));
+    board.clock = Some(clock.clone());
+    let result = board.game_result().unwrap();
+    assert_eq!(result.winner, Some(false));
+    assert_eq!(result.termination, Termination::Timeout);
+
+    // only the side to move can lose on time
+    let mut board = Board::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR b KQkq - 0 1")
+        .ok()
+        .unwrap();
+    board.clock = Some(clock.clone());
+    assert!(board.game_result().is_none());
+
+    // a lone king can't win on time
+    let mut board = Board::from_vec(&vec![
+        (0, 0, '♔', true),
+        (7, 7, '♔', false),
+        (3, 3, '♕', true),
+    ]);
+    board.clock = Some(clock.clone());
+    let result = board.game_result().unwrap();
+    assert_eq!(result.winner, None);
+    assert_eq!(result.termination, Termination::Timeout);
+
+    // a lone knight can still mate while the flagged side has a pawn in the way
+    let mut board = Board::from_fen("4k3/8/8/3n4/8/8/4P3/4K3 w - - 0 1")
+        .ok()
+        .unwrap();
+    board.clock = Some(clock.clone());
+    let result = board.game_result().unwrap();
+    assert_eq!(result.winner, Some(false));
+    assert_eq!(result.termination, Termination::Timeout);
+
+    // but not against a bare king
+    let mut board = Board::from_fen("4k3/8/8/3n4/8/8/8/4K3 w - - 0 1")
+        .ok()
+        .unwrap();
+    board.clock = Some(clock);
+    let result = board.game_result().unwrap();
+    assert_eq!(result.winner, None);
+    assert_eq!(result.termination, Termination::Timeout);
+}
+
+#[test]
+fn moves_press_the_clock() {
+    let mut board = Board::new();
+    board.clock = Some(Clock::new(TimeControl::SuddenDeath { base: seconds(60) }));
+    board.make_move("e4");
+    assert_eq!(board.clock.as_ref().unwrap().running(), Some(false));
+}
+
+#[test]
+fn allocate() {
+    // a share of the time left, plus most of the increment
+    assert_eq!(
+        clock::allocate(seconds(60), Duration::ZERO, Some(10)),
+        seconds(6)
+    );
+    assert_eq!(
+        clock::allocate(seconds(60), seconds(4), Some(10)),
+        seconds(9)
+    );
+
+    // never more than what is left
+    assert!(clock::allocate(seconds(1), seconds(10), Some(1)) < seconds(1));
+}
+
+#[test]
+fn format() {
+    assert_eq!(clock::format(seconds(5 * 60)), "5:00");
+    assert_eq!(clock::format(seconds(90 * 60)), "1:30:00");
+    assert_eq!(clock::format(Duration::from_millis(9_450)), "0:09.4");
+}
@@ -0,0 +1,147 @@
+use super::from_fen;
+use crate::board::Board;
+use crate::endgame::{self, Dtm, Endgame, Endgames};
+use crate::search::{Limits, Search, MATE};
+use std::fs;
+use std::sync::{Arc, OnceLock};
+
+/// Tables shared by the tests, since they take a while to generate
+/// * `KPvK` also makes `KQvK` and `KRvK`, which promotions lead to
+fn endgames() -> Arc<Endgames> {
+    static ENDGAMES: OnceLock<Arc<Endgames>> = OnceLock::new();
+    ENDGAMES
+        .get_or_init(|| {
+            let mut endgames = Endgames::new();
+            endgames.generate("KPvK").ok().unwrap();
+            Arc::new(endgames)
+        })
+        .clone()
+}
+
+#[test]
+fn generate() {
+    let endgames = endgames();
+    assert_eq!(endgames.names(), vec!["KPvK", "KQvK", "KRvK"]);
+
+    // longest mates are known for each ending
+    let longest = |name: &str| endgames.get(name).and_then(|endgame| endgame.longest());
+    assert_eq!(longest("KQvK"), Some(Dtm::Win(19)));
+    assert_eq!(longest("KRvK"), Some(Dtm::Win(31)));
+    assert_eq!(longest("KPvK"), Some(Dtm::Win(55)));
+}
+
+#[test]
+fn generate_invalid() {
+    let mut endgames = Endgames::new();
+    assert!(endgames.generate("KQ").is_err());
+    assert!(endgames.generate("QvK").is_err());
+    assert!(endgames.generate("KQRvKR").is_err());
+    assert!(endgames.names().is_empty());
+}
+
+#[test]
+fn name() {
+    assert_eq!(
+        endgame::name(&from_fen("8/8/4k3/8/2r5/8/1P6/1R2K3 w - - 0 1")),
+        "KRPvKR"
+    );
+    assert_eq!(
+        endgame::name(&Board::new()),
+        "KQRRBBNNPPPPPPPPvKQRRBBNNPPPPPPPP"
+    );
+}
+
+#[test]
+fn probe() {
+    let endgames = endgames();
+    let probe = |fen: &str| endgames.probe(&from_fen(fen));
+
+    assert_eq!(probe("7k/8/5K2/8/8/8/8/6Q1 w - - 0 1"), Some(Dtm::Win(1)));
+    assert_eq!(probe("6Qk/5K2/8/8/8/8/8/8 b - - 0 1"), Some(Dtm::Loss(0)));
+    assert_eq!(probe("8/8/8/3k4/8/8/8/KQ6 w - - 0 1"), Some(Dtm::Win(17)));
+    assert_eq!(probe("7k/8/6K1/8/8/8/8/R7 w - - 0 1"), Some(Dtm::Win(1)));
+
+    // stalemate, and a king in front of the pawn
+    assert_eq!(probe("7k/8/6QK/8/8/8/8/8 b - - 0 1"), Some(Dtm::Draw));
+    assert_eq!(probe("8/8/8/8/8/4k3/4P3/4K3 w - - 0 1"), Some(Dtm::Draw));
+    assert_eq!(probe("4k3/8/4K3/4P3/8/8/8/8 w - - 0 1"), Some(Dtm::Win(21)));
+
+    // black's pieces are looked up with the colours swapped
+    assert_eq!(
+        probe("4k3/8/8/8/8/8/8/3qK3 w - - 0 1"),
+        probe("3Qk3/8/8/8/8/8/8/4K3 b - - 0 1")
+    );
+    assert_eq!(probe("4k3/4p3/4K3/8/8/8/8/8 b - - 0 1"), Some(Dtm::Draw));
+    assert_eq!(probe("8/8/8/8/4p3/4k3/8/4K3 b - - 0 1"), Some(Dtm::Win(21)));
+
+    // no tables for other endings, and bare kings never need one
+    assert_eq!(probe("4k3/8/8/8/8/8/8/2B1K3 w - - 0 1"), Some(Dtm::Draw));
+    assert_eq!(probe("4k3/8/8/8/8/8/8/1NB1K3 w - - 0 1"), None);
+    assert_eq!(probe("r3k3/8/8/8/8/8/8/4K3 b q - 0 1"), None);
+}
+
+#[test]
+fn probe_en_passant() {
+    // every position drawn, since generating the real table takes minutes
+    let size: u32 = 2 * 32 * 64 * 64 * 64;
+    let mut bytes = b"DTM1\x05KPvKP".to_vec();
+    bytes.extend(size.to_be_bytes());
+    bytes.extend([0, 0x80, 0x80, 0x80, 0x08]);
+    let mut endgames = Endgames::new();
+    endgames.insert(Endgame::from_bytes(&bytes).ok().unwrap());
+
+    // the tables don't know about en passant, so only probe without a capture
+    let probe = |fen: &str| endgames.probe(&from_fen(fen));
+    assert_eq!(probe("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 2"), None);
+    assert_eq!(probe("4k3/8/8/2p1P3/8/8/8/4K3 w - c6 0 2"), Some(Dtm::Draw));
+    assert_eq!(probe("4k3/8/8/3pP3/8/8/8/4K3 w - - 0 2"), Some(Dtm::Draw));
+}
+
+#[test]
+fn to_bytes() {
+    let endgames = endgames();
+    let endgame = endgames.get("KRvK").unwrap();
+    let bytes = endgame.to_bytes();
+    assert!(bytes.len() < 2 * 10 * 64 * 64);
+
+    let read = Endgame::from_bytes(&bytes).ok().unwrap();
+    assert_eq!(read.name(), "KRvK");
+    assert_eq!(read.to_bytes(), bytes);
+    assert_eq!(read.longest(), Some(Dtm::Win(31)));
+
+    // cut short or made for something else
+    assert!(Endgame::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+    assert!(Endgame::from_bytes(b"DTM0").is_err());
+}
+
+#[test]
+fn save() {
+    let directory = std::env::temp_dir().join(format!("chess-endgame-{}", std::process::id()));
+    fs::create_dir_all(&directory).ok().unwrap();
+    endgames().save(&directory).ok().unwrap();
+
+    let opened = Endgames::open(&directory).ok().unwrap();
+    assert_eq!(opened.names(), vec!["KPvK", "KQvK", "KRvK"]);
+    let board = from_fen("4k3/8/4K3/4P3/8/8/8/8 b - - 0 1");
+    assert_eq!(opened.probe(&board), endgames().probe(&board));
+    let _ = fs::remove_dir_all(directory);
+}
+
+#[test]
+fn search() {
+    // the tables know the mate straight away, even without any depth to search
+    let board = from_fen("8/8/8/3k4/8/8/8/KQ6 w - - 0 1");
+    let mut search = Search::new(Limits {
+        depth: Some(1),
+        time: None,
+    });
+    search.endgames = Some(endgames());
+    let result = search.run(&board, |_| ());
+    assert_eq!(result.score, MATE - 17);
+    assert_eq!(result.mate_in(), Some(9));
+
+    // and keep to the shortest mate after the move
+    let mut board = board;
+    board.play(&result.best_move.unwrap());
+    assert_eq!(endgames().probe(&board), Some(Dtm::Loss(16)));
+}
@@ -0,0 +1,136 @@
+use super::{from_fen, square};
+use crate::board::Board;
+use crate::coordinate::Coordinate;
+use crate::pieces::Id;
+use crate::{Castling, Error, Line, MoveError};
+
+#[test]
+fn notation() {
+    let board = Board::new();
+    assert_eq!(
+        board.parse_move("Nz3").err(),
+        Some(Error::Notation {
+            input: String::from("Nz3"),
+            token: String::from("z3"),
+            position: 1,
+        })
+    );
+    assert_eq!(
+        board.parse_move("Nb1c2d3").err(),
+        Some(Error::Notation {
+            input: String::from("Nb1c2d3"),
+            token: String::from("b1c2"),
+            position: 1,
+        })
+    );
+    assert_eq!(
+        board.parse_move("!e4").err(),
+        Some(Error::Notation {
+            input: String::from("!e4"),
+            token: String::from("!"),
+            position: 0,
+        })
+    );
+    assert!(matches!(board.parse_move("e"), Err(Error::Notation { .. })));
+
+    let error = board.parse_move("Nz3").err().unwrap();
+    assert_eq!(error.to_string(), "unexpected `z3` at character 2 of `Nz3`");
+    assert_eq!(
+        error.reason(),
+        "is not valid notation, unexpected `z3` at character 2 of `Nz3`"
+    );
+}
+
+#[test]
+fn unreachable() {
+    let board = Board::new();
+    assert_eq!(
+        board.parse_move("Nf4").err(),
+        Some(Error::Move(MoveError::Unreachable {
+            id: Id::Knight,
+            target: square("f4"),
+            blocked: None,
+        }))
+    );
+    assert_eq!(
+        board.parse_move("Nf4").err().unwrap().to_string(),
+        "no knight can reach f4"
+    );
+}
+
+#[test]
+fn ambiguous() {
+    let board = from_fen("4k3/8/8/8/8/8/8/1N2KN2 w - - 0 1");
+    let error = board.parse_move("Nd2").err().unwrap();
+    assert_eq!(
+        error,
+        Error::Move(MoveError::Ambiguous {
+            id: Id::Knight,
+            target: square("d2"),
+            candidates: vec![square("b1"), square("f1")],
+        })
+    );
+    assert_eq!(error.to_string(), "knights on b1 and f1 can both reach d2");
+}
+
+#[test]
+fn pinned() {
+    let board = from_fen("4k3/8/8/8/1b6/8/3N4/4K3 w - - 0 1");
+    let error = board.parse_move("Nf3").err().unwrap();
+    assert_eq!(
+        error,
+        Error::Move(MoveError::Pinned {
+            id: Id::Knight,
+            from: square("d2"),
+            king: square("e1"),
+            pinner: Id::Bishop,
+            by: square("b4"),
+            line: Line::Diagonal,
+        })
+    );
+    assert_eq!(
+        error.reason(),
+        "is not possible, the knight on d2 is pinned to the king on e1 by the bishop on b4 along \
+         the diagonal"
+    );
+
+    // already in check, so the knight isn't what's wrong
+    let board = from_fen("4k3/8/8/8/1b6/8/3N4/4K2r w - - 0 1");
+    assert_eq!(
+        board.parse_move("Nf3").err(),
+        Some(Error::Move(MoveError::KingInCheck {
+            checkers: vec![(Id::Rook, square("h1")), (Id::Bishop, square("b4"))],
+        }))
+    );
+}
+
+#[test]
+fn display() {
+    let error: Box<dyn std::error::Error> = Box::new(Error::InvalidSideToMove {
+        value: String::from("x"),
+    });
+    assert_eq!(error.to_string(), "invalid side to move `x`");
+    assert_eq!(
+        Board::from_fen("4k3/8/8/8/8/8/8/4X3 w - - 0 1")
+            .err()
+            .unwrap()
+            .to_string(),
+        "invalid piece placement `4k3/8/8/8/8/8/8/4X3`"
+    );
+    assert_eq!(
+        Error::from(MoveError::CannotCastle {
+            kingside: false,
+            cause: Castling::KingMoved,
+        })
+        .to_string(),
+        "cannot castle queenside, the king has already moved"
+    );
+    assert_eq!(
+        Coordinate::new(8, 0).err(),
+        Some(Error::OutOfBounds { x: 8, y: 0 })
+    );
+    assert_eq!(
+        Id::from_char('X').err(),
+        Some(Error::UnknownPiece { letter: 'X' })
+    );
+}
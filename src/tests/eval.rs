@@ -0,0 +1,98 @@
+use crate::board::Board;
+use crate::eval::{evaluate, evaluate_with, Weights};
+
+/// Evaluates a position given in FEN for the side to move
+fn score(fen: &str) -> i32 {
+    let board = Board::from_fen(fen).ok().unwrap();
+    let white = board.state.white;
+    evaluate(&board, white)
+}
+
+#[test]
+fn starting_position() {
+    let board = Board::new();
+    assert_eq!(evaluate(&board, true), 0);
+    assert_eq!(evaluate(&board, false), 0);
+}
+
+#[test]
+fn side_to_move() {
+    let fen = "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR";
+    let white = score(&format!("{} w KQkq - 0 1", fen));
+    let black = score(&format!("{} b KQkq - 0 1", fen));
+    assert!(white > 0);
+    assert_eq!(white, -black);
+}
+
+#[test]
+fn mirrored() {
+    let white = score("4k3/8/8/8/8/2N5/1P6/4K3 w - - 0 1");
+    let black = score("4k3/1p6/2n5/8/8/8/8/4K3 b - - 0 1");
+    assert_eq!(white, black);
+}
+
+#[test]
+fn material() {
+    assert!(score("4k3/8/8/8/8/8/8/3QK3 w - - 0 1") > 800);
+    assert!(score("4k3/8/8/8/8/8/8/3QK3 b - - 0 1") < -800);
+}
+
+#[test]
+fn centralised_knight() {
+    let rim = score("4k3/8/8/8/8/8/8/N3K3 w - - 0 1");
+    let centre = score("4k3/8/8/8/3N4/8/8/4K3 w - - 0 1");
+    assert!(centre > rim);
+}
+
+#[test]
+fn pawn_structure() {
+    let healthy = score("4k3/8/8/8/8/8/3PP3/4K3 w - - 0 1");
+    let doubled = score("4k3/8/8/8/8/3P4/3P4/4K3 w - - 0 1");
+    let isolated = score("4k3/8/8/8/8/8/2P1P3/4K3 w - - 0 1");
+    assert!(healthy > doubled);
+    assert!(healthy > isolated);
+}
+
+#[test]
+fn passed_pawn() {
+    let board = Board::from_fen("4k3/8/8/3P4/8/8/8/4K3 w - - 0 1")
+        .ok()
+        .unwrap();
+    let white = board.state.white;
+    let weights = Weights {
+        passed_pawn: [(0, 0); 8],
+        ..Default::default()
+    };
+    assert!(evaluate(&board, white) > evaluate_with(&board, white, &weights));
+
+    let behind = score("4k3/8/8/8/8/8/3P4/4K3 w - - 0 1");
+    let advanced = score("4k3/8/3P4/8/8/8/8/4K3 w - - 0 1");
+    assert!(advanced > behind);
+}
+
+#[test]
+fn tapering() {
+    // the king belongs in the corner with queens on, but in the centre without them
+    let sheltered = "rnbq1bnr/pppppppp/4k3/8/8/8/PPPPPPPP/RNBQ1RK1 w - - 0 1";
+    let central = "rnbq1bnr/pppppppp/4k3/8/3K4/8/PPPPPPPP/RNBQ1R2 w - - 0 1";
+    assert!(score(sheltered) > score(central));
+
+    let sheltered = "8/8/4k3/8/8/8/8/6K1 w - - 0 1";
+    let central = "8/8/4k3/8/3K4/8/8/8 w - - 0 1";
+    assert!(score(central) > score(sheltered));
+}
+
+#[test]
+fn custom_weights() {
+    let board = Board::from_fen("4k3/8/8/8/8/8/8/3QK3 w - - 0 1")
+        .ok()
+        .unwrap();
+    let white = board.state.white;
+    let mut material = Weights::default().material;
+    material[4] = (0, 0);
+    let weights = Weights {
+        material,
+        ..Default::default()
+    };
+    assert!(evaluate_with(&board, white, &weights) < evaluate(&board, white) - 800);
+}
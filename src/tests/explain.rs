@@ -0,0 +1,135 @@
+use super::{from_fen, square};
+use crate::board::Board;
+use crate::pieces::Id;
+use crate::{Castling, Error, Line, MoveError};
+
+/// Reason given for rejecting a move
+fn reason(board: &Board, input: &str) -> String {
+    board.parse_move(input).err().unwrap().reason()
+}
+
+#[test]
+fn checkers() {
+    // walking into check, and ignoring a check
+    let board = from_fen("4r1k1/8/8/8/8/8/3B4/4K3 w - - 0 1");
+    assert_eq!(
+        board.parse_move("Ke2").err(),
+        Some(Error::Move(MoveError::KingInCheck {
+            checkers: vec![(Id::Rook, square("e8"))],
+        }))
+    );
+    assert_eq!(
+        reason(&board, "Bc3"),
+        "puts the king in check from the rook on e8"
+    );
+
+    // both pieces giving check are named
+    let board = from_fen("4r1k1/8/8/1b6/8/8/8/3K4 w - - 0 1");
+    assert_eq!(
+        board.parse_move("Ke2").err().unwrap().to_string(),
+        "the bishop on b5 and the rook on e8 would give check"
+    );
+}
+
+#[test]
+fn pins() {
+    let board = from_fen("4r1k1/8/8/8/8/8/4R3/4K3 w - - 0 1");
+    assert_eq!(
+        board.parse_move("Rd2").err(),
+        Some(Error::Move(MoveError::Pinned {
+            id: Id::Rook,
+            from: square("e2"),
+            king: square("e1"),
+            pinner: Id::Rook,
+            by: square("e8"),
+            line: Line::File,
+        }))
+    );
+
+    let board = from_fen("6k1/8/8/8/8/8/8/r2NK3 w - - 0 1");
+    assert_eq!(
+        reason(&board, "Nf2"),
+        "is not possible, the knight on d1 is pinned to the king on e1 by the rook on a1 along \
+         the rank"
+    );
+}
+
+#[test]
+fn blockers() {
+    let board = Board::new();
+    assert_eq!(
+        board.parse_move("Ra3").err(),
+        Some(Error::Move(MoveError::Unreachable {
+            id: Id::Rook,
+            target: square("a3"),
+            blocked: Some((square("a1"), square("a2"))),
+        }))
+    );
+    assert_eq!(
+        reason(&board, "Bc4"),
+        "is not possible, no bishop can reach c4, the bishop on f1 is blocked on e2"
+    );
+
+    // a piece of the same colour on the target
+    assert_eq!(
+        reason(&board, "Nd2"),
+        "is not possible, no knight can reach d2, the knight on b1 is blocked on d2"
+    );
+
+    // pawns are blocked straight ahead
+    let board = from_fen("4k3/8/8/8/8/4n3/4P3/4K3 w - - 0 1");
+    assert_eq!(
+        reason(&board, "e4"),
+        "is not possible, no pawn can reach e4, the pawn on e2 is blocked on e3"
+    );
+
+    // nothing in the way, the piece just doesn't move like that
+    assert_eq!(
+        reason(&Board::new(), "Nb4"),
+        "is not possible, no knight can reach b4"
+    );
+}
+
+#[test]
+fn castling() {
+    let cause = |board: &Board, input: &str| match board.parse_move(input) {
+        Err(Error::Move(MoveError::CannotCastle { cause, .. })) => Some(cause),
+        _ => None,
+    };
+
+    // pieces in the way, then squares under attack
+    let board = Board::new();
+    assert_eq!(
+        cause(&board, "O-O"),
+        Some(Castling::Blocked {
+            square: square("f1")
+        })
+    );
+    let board = from_fen("r3k2r/8/8/8/8/8/5r2/R3K2R w KQkq - 0 1");
+    assert_eq!(
+        cause(&board, "O-O"),
+        Some(Castling::Attacked {
+            square: square("f1")
+        })
+    );
+    assert_eq!(
+        reason(&board, "O-O"),
+        "is not possible, the king would pass through or land on f1, which is attacked"
+    );
+    let board = from_fen("r3k2r/8/8/8/8/8/4r3/R3K2R w KQkq - 0 1");
+    assert_eq!(cause(&board, "O-O-O"), Some(Castling::InCheck));
+
+    // rights given up in the FEN, then lost by moving
+    let board = from_fen("r3k2r/8/8/8/8/8/8/R3K2R w Kkq - 0 1");
+    assert_eq!(cause(&board, "O-O-O"), Some(Castling::RightsLost));
+
+    let mut board = from_fen("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1");
+    assert!(board.make_move("Ke2"));
+    assert!(board.make_move("Rh7"));
+    assert!(board.make_move("Ke1"));
+    assert!(board.make_move("Rh8"));
+    assert_eq!(cause(&board, "O-O"), Some(Castling::KingMoved));
+    assert!(board.make_move("Rb1"));
+    assert_eq!(cause(&board, "O-O"), Some(Castling::RookMoved));
+    assert!(board.make_move("O-O-O"));
+}
@@ -0,0 +1,119 @@
+use crate::board::Board;
+use crate::fen::STARTING_FEN;
+use crate::Error;
+
+/// Checks that a position survives being read and written
+fn test_round_trip(fen: &str) {
+    match Board::from_fen(fen) {
+        Ok(board) => assert_eq!(board.to_fen(), fen),
+        Err(error) => panic!("{}: {}", fen, error),
+    }
+}
+
+#[test]
+fn starting_position() {
+    assert_eq!(Board::new().to_fen(), STARTING_FEN);
+    let board = Board::from_fen(STARTING_FEN).ok().unwrap();
+    assert!(board.state.white);
+    assert_eq!(board.legal_moves().len(), 20);
+}
+
+#[test]
+fn round_trip() {
+    test_round_trip("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1");
+    test_round_trip("8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1");
+    test_round_trip("r3k2r/8/8/8/8/8/8/R3K2R b Kq - 3 17");
+}
+
+#[test]
+fn after_moves() {
+    let mut board = Board::new();
+    board.make_move("e4");
+    board.make_move("Nf6");
+    board.make_move("Nf3");
+    assert_eq!(
+        board.to_fen(),
+        "rnbqkb1r/pppppppp/5n2/8/4P3/5N2/PPPP1PPP/RNBQKB1R b KQkq - 2 2"
+    );
+}
+
+#[test]
+fn en_passant() {
+    let fen = "rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3";
+    test_round_trip(fen);
+    let mut board = Board::from_fen(fen).ok().unwrap();
+    assert!(board.make_move("exf6"));
+    assert!(board.grid[4][5].is_none());
+}
+
+#[test]
+fn castling_rights() {
+    let board = Board::from_fen("r3k2r/8/8/8/8/8/8/R3K2R w Qk - 0 1")
+        .ok()
+        .unwrap();
+    assert!(board.clone().make_move("O-O-O"));
+    assert!(!board.clone().make_move("O-O"));
+
+    let board = Board::from_fen("r3k2r/8/8/8/8/8/8/R3K2R b Qk - 0 1")
+        .ok()
+        .unwrap();
+    assert!(board.clone().make_move("O-O"));
+    assert!(!board.clone().make_move("O-O-O"));
+}
+
+#[test]
+fn chess960_castling_rights() {
+    test_round_trip("r3kr1r/8/8/8/8/8/8/R3KR1R w FQfq - 0 1");
+
+    // rook files are only needed when there is more than one rook on that side
+    let board = Board::from_fen("1r2k1r1/8/8/8/8/8/8/1R2K2R w HBgb - 0 1")
+        .ok()
+        .unwrap();
+    assert_eq!(board.to_fen(), "1r2k1r1/8/8/8/8/8/8/1R2K2R w KQkq - 0 1");
+    assert!(board.clone().make_move("O-O-O"));
+}
+
+#[test]
+fn optional_counters() {
+    let board = Board::from_fen("8/8/8/4k3/8/8/8/4K3 b - -").ok().unwrap();
+    assert!(!board.state.white);
+    assert_eq!(board.state.halfmove_clock, 0);
+    assert_eq!(board.state.fullmove_number, 1);
+}
+
+#[test]
+fn invalid_fields() {
+    let kings = "4k3/8/8/8/8/8/8/4K3";
+    assert!(matches!(
+        Board::from_fen("4k3/8/8/8/8/8/8/4K2 w - - 0 1"),
+        Err(Error::InvalidPlacement { .. })
+    ));
+    assert!(matches!(
+        Board::from_fen("4k3/8/8/8/8/8/8/4X3 w - - 0 1"),
+        Err(Error::InvalidPlacement { .. })
+    ));
+    assert!(matches!(
+        Board::from_fen("4k3/8/8/8/8/8/4K3 w - - 0 1"),
+        Err(Error::InvalidPlacement { .. })
+    ));
+    assert!(matches!(
+        Board::from_fen(&format!("{} x - - 0 1", kings)),
+        Err(Error::InvalidSideToMove { .. })
+    ));
+    assert!(matches!(
+        Board::from_fen(&format!("{} w KQ - 0 1", kings)),
+        Err(Error::InvalidCastlingRights { .. })
+    ));
+    assert!(matches!(
+        Board::from_fen(&format!("{} w - e3 0 1", kings)),
+        Err(Error::InvalidEnPassant { .. })
+    ));
+    assert!(matches!(
+        Board::from_fen(&format!("{} w - - x 1", kings)),
+        Err(Error::InvalidHalfmoveClock { .. })
+    ));
+    assert!(matches!(
+        Board::from_fen(&format!("{} w - - 0 0", kings)),
+        Err(Error::InvalidFullmoveNumber { .. })
+    ));
+}
@@ -0,0 +1,104 @@
+use crate::board::Board;
+use crate::game::{insufficient_material, GameResult, Termination};
+
+#[test]
+fn checkmate() {
+    let mut board = Board::new();
+    board.make_move("f3");
+    board.make_move("e6");
+    board.make_move("g4");
+    board.make_move("Qh4");
+    let result = board.game_result().unwrap();
+    assert_eq!(result.winner, Some(false));
+    assert_eq!(result.termination, Termination::Checkmate);
+    assert_eq!(result.to_string(), "Black has won!");
+}
+
+#[test]
+fn stalemate() {
+    let board = Board::from_fen("k7/2K5/1Q6/8/8/8/8/8 b - - 0 1")
+        .ok()
+        .unwrap();
+    let result = board.game_result().unwrap();
+    assert_eq!(result.winner, None);
+    assert_eq!(result.termination, Termination::Stalemate);
+    assert_eq!(result.to_string(), "Draw by stalemate!");
+}
+
+#[test]
+fn in_progress() {
+    let board = Board::new();
+    assert!(board.game_result().is_none());
+}
+
+#[test]
+fn threefold_repetition() {
+    let mut board = Board::new();
+    for _ in 0..2 {
+        assert!(board.game_result().is_none());
+        board.make_move("Nf3");
+        board.make_move("Nf6");
+        board.make_move("Ng1");
+        board.make_move("Ng8");
+    }
+    let result = board.game_result().unwrap();
+    assert_eq!(result.termination, Termination::ThreefoldRepetition);
+    assert_eq!(board.positions.last(), Some(&Board::new().hash));
+    assert_eq!(board.positions.len(), 9);
+}
+
+#[test]
+fn fifty_move_rule() {
+    let mut board = Board::from_vec(&vec![
+        (0, 0, '♔', true),
+        (0, 1, '♖', true),
+        (7, 7, '♔', false),
+    ]);
+    board.state.halfmove_clock = 98;
+    board.make_move("Rb2");
+    assert!(board.game_result().is_none());
+    board.make_move("Kg8");
+    let result = board.game_result().unwrap();
+    assert_eq!(result.termination, Termination::FiftyMoveRule);
+}
+
+#[test]
+fn clock_reset() {
+    let mut board = Board::new();
+    board.make_move("Nf3");
+    board.make_move("Nf6");
+    assert_eq!(board.state.halfmove_clock, 2);
+    board.make_move("e4");
+    assert_eq!(board.state.halfmove_clock, 0);
+}
+
+#[test]
+fn insufficient() {
+    let kings = vec![(0, 0, '♔', true), (7, 7, '♔', false)];
+    assert!(insufficient_material(&Board::from_vec(&kings)));
+
+    let mut knight = kings.clone();
+    knight.push((3, 3, '♘', true));
+    assert!(insufficient_material(&Board::from_vec(&knight)));
+
+    // bishops on the same colour
+    let mut bishops = kings.clone();
+    bishops.push((2, 0, '♗', true));
+    bishops.push((5, 7, '♗', false));
+    let board = Board::from_vec(&bishops);
+    assert!(insufficient_material(&board));
+    assert_eq!(
+        GameResult::from_board(&board).unwrap().termination,
+        Termination::InsufficientMaterial
+    );
+
+    // bishops on opposite colours
+    let mut bishops = kings.clone();
+    bishops.push((2, 0, '♗', true));
+    bishops.push((2, 7, '♗', false));
+    assert!(!insufficient_material(&Board::from_vec(&bishops)));
+
+    let mut pawn = kings.clone();
+    pawn.push((3, 3, '♙', true));
+    assert!(!insufficient_material(&Board::from_vec(&pawn)));
+}
@@ -0,0 +1,120 @@
+use crate::board::Board;
+use crate::pieces::moves::MoveType;
+
+/// Counts the moves that match a predicate
+fn count(moves: &[MoveType], predicate: fn(&MoveType) -> bool) -> usize {
+    moves
+        .iter()
+        .filter(|move_type| predicate(move_type))
+        .count()
+}
+
+#[test]
+fn starting_position() {
+    let board = Board::new();
+    assert_eq!(board.legal_moves().len(), 20);
+    let board = Board::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR b KQkq - 0 1")
+        .ok()
+        .unwrap();
+    assert_eq!(board.legal_moves().len(), 20);
+}
+
+#[test]
+fn promotion() {
+    let board = Board::from_vec(&vec![
+        (0, 6, '♙', true),
+        (4, 0, '♔', true),
+        (4, 7, '♔', false),
+    ]);
+    let moves = board.legal_moves();
+    let promotions = count(&moves, |move_type| match move_type {
+        MoveType::Normal { promotion, .. } => promotion.is_some(),
+        _ => false,
+    });
+    assert_eq!(promotions, 4);
+}
+
+#[test]
+fn castle() {
+    let board = Board::from_vec(&vec![
+        (4, 0, '♔', true),
+        (0, 0, '♖', true),
+        (7, 0, '♖', true),
+    ]);
+    let moves = board.legal_moves();
+    let castles = count(&moves, |move_type| {
+        matches!(move_type, MoveType::Castle { .. })
+    });
+    assert_eq!(castles, 2);
+}
+
+#[test]
+fn castle_960_shielded() {
+    // the rook on b1 blocks the queen until it moves to d1
+    let board = Board::from_vec(&vec![
+        (2, 0, '♔', true),
+        (1, 0, '♖', true),
+        (0, 0, '♕', false),
+        (7, 7, '♔', false),
+    ]);
+    let moves = board.legal_moves();
+    let castles = count(&moves, |move_type| {
+        matches!(move_type, MoveType::Castle { .. })
+    });
+    assert_eq!(castles, 0);
+}
+
+#[test]
+fn en_passant() {
+    let mut board = Board::from_fen("4k3/p7/8/1P6/8/8/8/4K3 b - - 0 1")
+        .ok()
+        .unwrap();
+    board.make_move("a5");
+    let moves = board.legal_moves();
+    let captures = count(&moves, |move_type| {
+        matches!(move_type, MoveType::EnPassant { .. })
+    });
+    assert_eq!(captures, 1);
+}
+
+#[test]
+fn pinned() {
+    let board = Board::from_vec(&vec![
+        (4, 0, '♔', true),
+        (4, 1, '♘', true),
+        (4, 7, '♖', false),
+    ]);
+    let moves = board.legal_moves();
+    let knight_moves = count(&moves, |move_type| match move_type {
+        MoveType::Normal { piece, .. } => piece.icon == '♘',
+        _ => false,
+    });
+    assert_eq!(knight_moves, 0);
+}
+
+#[test]
+fn stalemate() {
+    let board = Board::from_fen("k7/2K5/1Q6/8/8/8/8/8 b - - 0 1")
+        .ok()
+        .unwrap();
+    assert!(board.legal_moves().is_empty());
+}
+
+#[test]
+fn play() {
+    let mut board = Board::new();
+    let moves = board.legal_moves();
+    let push = moves
+        .iter()
+        .find(|move_type| match move_type {
+            MoveType::Normal { piece, target, .. } => {
+                piece.position.x == 4 && piece.position.y == 1 && target.y == 3
+            }
+            _ => false,
+        })
+        .unwrap();
+    board.play(push);
+    assert!(board.grid[1][4].is_none());
+    assert!(board.grid[3][4].is_some());
+    assert_eq!(board.history.last().unwrap(), "e4");
+}
@@ -1,6 +1,7 @@
 use super::test_move;
 use crate::board::Board;
 use crate::coordinate::Coordinate;
+use crate::pieces::moves::MoveType;
 use crate::pieces::MoveChecker;
 
 #[test]
@@ -48,25 +49,61 @@ fn capture() {
 
 #[test]
 fn en_passant() {
-    let mut board = Board::from_vec(&vec![
-        (1, 4, '♙', true),
-        (0, 6, '♙', false),
-        (2, 6, '♙', false),
-    ]);
-    board.make_move("c6", false);
-    board.make_move("c5", false);
-    match MoveChecker::en_passant(&board, Some(1), &Coordinate { x: 2, y: 5 }, true) {
-        Some(_) => assert!(false),
-        None => assert!(true),
-    };
-    board.make_move("a5", false);
-    match MoveChecker::en_passant(&board, Some(1), &Coordinate { x: 0, y: 5 }, true) {
-        Some((x, y)) => {
-            assert_eq!(x, 1);
-            assert_eq!(y, 4);
-        }
-        None => assert!(false),
-    };
+    let mut board = Board::from_fen("8/p1p5/8/1P6/8/8/8/K7 b - - 0 1")
+        .ok()
+        .unwrap();
+    board.make_move("c6");
+    board.make_move("Ka2");
+    board.make_move("c5");
+    assert_eq!(
+        MoveChecker::en_passant(&board, Some(1), &Coordinate { x: 2, y: 5 }, true),
+        None
+    );
+    board.make_move("Ka1");
+    board.make_move("a5");
+    assert_eq!(
+        MoveChecker::en_passant(&board, Some(1), &Coordinate { x: 0, y: 5 }, true),
+        Some((1, 4))
+    );
+}
+
+#[test]
+fn en_passant_only_by_pawns() {
+    // a knight next to the pawn can't take it en passant
+    let mut board = Board::from_fen("4k3/8/8/2NpP3/8/8/8/4K3 w - d6 0 1")
+        .ok()
+        .unwrap();
+    let mut pawn = board.clone();
+    assert!(pawn.try_move("d6").is_ok());
+    assert_eq!(pawn.to_fen(), "4k3/8/3P4/2N5/8/8/8/4K3 b - - 0 1");
+    assert!(board.try_move("Nxd6").is_err());
+
+    let mut board = Board::from_fen("4k3/8/8/2Np4/8/8/8/4K3 w - d6 0 1")
+        .ok()
+        .unwrap();
+    let fen = board.to_fen();
+    assert!(board.try_move("d6").is_err());
+    assert_eq!(board.to_fen(), fen);
+
+    // nor can the other side's pieces be used to capture
+    let mut board = Board::from_fen("4k3/8/8/2rpP3/8/8/8/4K3 w - d6 0 1")
+        .ok()
+        .unwrap();
+    assert!(board.try_move("cxd6").is_err());
+    assert_eq!(
+        board
+            .legal_moves()
+            .iter()
+            .filter(|m| matches!(m, MoveType::EnPassant { .. }))
+            .count(),
+        1
+    );
+
+    // and the pawn has to be next to the one it captures
+    let mut board = Board::from_fen("4k3/8/8/P2pP3/8/8/8/4K3 w - d6 0 1")
+        .ok()
+        .unwrap();
+    assert!(board.try_move("axd6").is_err());
 }
 
 #[test]
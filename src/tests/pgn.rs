@@ -0,0 +1,136 @@
+use crate::board::Board;
+use crate::fen::STARTING_FEN;
+use crate::pgn::Pgn;
+use crate::Error;
+
+const SCHOLARS_MATE: &str = r#"[Event "Casual Game"]
+[Site "?"]
+[Date "2023.01.01"]
+[Round "1"]
+[White "Alice"]
+[Black "Bob \"The Builder\""]
+[Result "1-0"]
+
+1. e4 {best by test} e5 2. Bc4 (2. Nf3 Nc6) Nc6 3. Qh5 Nf6?? $4
+4. Qxf7# 1-0
+"#;
+
+#[test]
+fn parse() {
+    let pgn = Pgn::parse(SCHOLARS_MATE).ok().unwrap();
+    assert_eq!(pgn.tag("White"), Some("Alice"));
+    assert_eq!(pgn.tag("Black"), Some("Bob \"The Builder\""));
+    assert_eq!(pgn.result, "1-0");
+    assert_eq!(
+        pgn.moves,
+        vec!["e4", "e5", "Bc4", "Nc6", "Qh5", "Nf6", "Qxf7#"]
+    );
+}
+
+#[test]
+fn replay() {
+    let pgn = Pgn::parse(SCHOLARS_MATE).ok().unwrap();
+    let board = pgn.replay().ok().unwrap();
+    assert!(!board.state.white);
+    let result = board.game_result().unwrap();
+    assert_eq!(result.score(), "1-0");
+}
+
+#[test]
+fn round_trip() {
+    let pgn = Pgn::parse(SCHOLARS_MATE).ok().unwrap();
+    let text = pgn.to_string();
+    assert!(text.starts_with("[Event \"Casual Game\"]\n"));
+    assert!(text.contains("[Black \"Bob \\\"The Builder\\\"\"]\n"));
+    assert!(text.ends_with("\n\n1. e4 e5 2. Bc4 Nc6 3. Qh5 Nf6 4. Qxf7# 1-0\n"));
+
+    let reparsed = Pgn::parse(&text).ok().unwrap();
+    assert_eq!(reparsed.tags, pgn.tags);
+    assert_eq!(reparsed.moves, pgn.moves);
+}
+
+#[test]
+fn from_board() {
+    let mut board = Board::new();
+    board.make_move("e4");
+    board.make_move("c5");
+    let pgn = Pgn::from_board(&board, STARTING_FEN);
+    assert_eq!(pgn.tag("Event"), Some("?"));
+    assert_eq!(pgn.tag("Result"), Some("*"));
+    assert!(pgn.tag("FEN").is_none());
+    assert!(pgn.to_string().ends_with("\n\n1. e4 c5 *\n"));
+}
+
+#[test]
+fn set_up() {
+    let fen = "4k3/8/8/8/8/8/4P3/4K3 b - - 0 12";
+    let mut board = Board::from_fen(fen).ok().unwrap();
+    board.make_move("Kd7");
+    board.make_move("e4");
+    let pgn = Pgn::from_board(&board, fen);
+    assert_eq!(pgn.tag("SetUp"), Some("1"));
+    assert!(pgn.to_string().ends_with("\n\n12... Kd7 13. e4 *\n"));
+
+    let replayed = Pgn::parse(&pgn.to_string())
+        .ok()
+        .unwrap()
+        .replay()
+        .ok()
+        .unwrap();
+    assert!(!replayed.state.white);
+    assert_eq!(replayed.to_fen(), board.to_fen());
+}
+
+#[test]
+fn line_width() {
+    let mut pgn = Pgn::new();
+    for _ in 0..20 {
+        pgn.moves
+            .extend(["Nf3", "Nf6", "Ng1", "Ng8"].map(String::from));
+    }
+    for line in pgn.to_string().lines() {
+        assert!(line.len() < 80);
+    }
+}
+
+#[test]
+fn illegal_move() {
+    let pgn = Pgn::parse("1. e4 e5 2. Ke3 *").ok().unwrap();
+    match pgn.replay() {
+        Err(Error::IllegalMove { ply, input, .. }) => {
+            assert_eq!(ply, 3);
+            assert_eq!(input, "Ke3");
+        }
+        other => panic!("expected an illegal move, got {:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+fn invalid() {
+    assert!(matches!(
+        Pgn::parse("[Event \"Unclosed"),
+        Err(Error::InvalidPgn { .. })
+    ));
+    assert!(matches!(
+        Pgn::parse("1. e4 { never closed"),
+        Err(Error::InvalidPgn { .. })
+    ));
+    assert!(matches!(
+        Pgn::parse("1. e4 (1. d4 *"),
+        Err(Error::InvalidPgn { .. })
+    ));
+}
+
+#[test]
+fn parse_all() {
+    let text =
+        "[Event \"1\"]\n\n1. e4 e5 1-0\n\n[Event \"2\"]\n[Result \"0-1\"]\n\n1. d4 d5\n2. c4 0-1\n";
+    let games = Pgn::parse_all(text).ok().unwrap();
+    assert_eq!(games.len(), 2);
+    assert_eq!(games[0].tag("Event"), Some("1"));
+    assert_eq!(games[0].moves, vec!["e4", "e5"]);
+    assert_eq!(games[1].moves, vec!["d4", "d5", "c4"]);
+    assert_eq!(games[1].result, "0-1");
+
+    assert!(Pgn::parse_all("").ok().unwrap().is_empty());
+}
@@ -0,0 +1,80 @@
+use crate::board::Board;
+
+/// Checks the SAN written for a move entered in any accepted notation
+fn test_san(fen: &str, input: &str, expected: &str) {
+    let board = Board::from_fen(fen).ok().unwrap();
+    match board.parse_move(input) {
+        Ok(move_type) => assert_eq!(board.to_san(&move_type), expected),
+        Err(error) => panic!("{}: {}", input, error),
+    }
+}
+
+#[test]
+fn quiet() {
+    let start = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+    test_san(start, "e4", "e4");
+    test_san(start, "Nf3", "Nf3");
+    test_san(start, "Ng1f3", "Nf3");
+}
+
+#[test]
+fn captures() {
+    let fen = "rnbqkbnr/ppp2ppp/8/3pp3/4P3/5N2/PPPP1PPP/RNBQKB1R w KQkq - 0 3";
+    test_san(fen, "ed5", "exd5");
+    test_san(fen, "Ne5", "Nxe5");
+}
+
+#[test]
+fn disambiguation() {
+    // knights on b1 and f3 can both reach d2
+    let fen = "4k3/8/8/8/8/5N2/8/RN2K2R w - - 0 1";
+    test_san(fen, "Nbd2", "Nbd2");
+    test_san(fen, "Rf1", "Rf1");
+
+    // rooks on the same file
+    let fen = "4k3/8/8/R7/8/8/8/R3K3 w - - 0 1";
+    test_san(fen, "R1a3", "R1a3");
+    test_san(fen, "R5a3", "R5a3");
+
+    // three queens can reach d4, so one needs the full square
+    let fen = "4k3/8/1Q6/8/8/8/1Q3Q2/7K w - - 0 1";
+    test_san(fen, "Qb2d4", "Qb2d4");
+    test_san(fen, "Qb6d4", "Q6d4");
+    test_san(fen, "Qf2d4", "Qfd4");
+}
+
+#[test]
+fn promotion() {
+    let fen = "3r2k1/4P3/8/8/8/8/8/4K3 w - - 0 1";
+    test_san(fen, "e8Q", "e8=Q+");
+    test_san(fen, "exd8=N", "exd8=N");
+}
+
+#[test]
+fn castle() {
+    let fen = "r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1";
+    test_san(fen, "0-0", "O-O");
+    test_san(fen, "O-O-O", "O-O-O");
+}
+
+#[test]
+fn en_passant() {
+    let fen = "4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 1";
+    test_san(fen, "exd6 e.p.", "exd6");
+}
+
+#[test]
+fn check_and_mate() {
+    let fen = "r1bqkb1r/pppp1ppp/2n2n2/4p2Q/2B1P3/8/PPPP1PPP/RNB1K1NR w KQkq - 4 4";
+    test_san(fen, "Qf7", "Qxf7#");
+    test_san(fen, "Bf7", "Bxf7+");
+}
+
+#[test]
+fn history() {
+    let mut board = Board::new();
+    board.make_move("e2e4");
+    board.make_move("e5");
+    board.make_move("Ng1f3");
+    assert_eq!(board.history, vec!["e4", "e5", "Nf3"]);
+}
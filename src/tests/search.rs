@@ -0,0 +1,85 @@
+use crate::board::Board;
+use crate::search::{search, Limits, MATE};
+use std::time::Duration;
+
+/// Searches a position to a fixed depth
+fn best_move(fen: &str, depth: usize) -> (String, i32) {
+    let board = Board::from_fen(fen).ok().unwrap();
+    let limits = Limits {
+        depth: Some(depth),
+        time: None,
+    };
+    let result = search(&board, limits);
+    let best = result.best_move.unwrap();
+    (board.to_san(&best), result.score)
+}
+
+#[test]
+fn mate_in_one() {
+    let (san, score) = best_move("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1", 2);
+    assert_eq!(san, "Ra8#");
+    assert_eq!(score, MATE - 1);
+}
+
+#[test]
+fn mate_in_two() {
+    // the rooks take turns cutting off the king
+    let board = Board::from_fen("7k/8/8/8/8/8/R7/1R4K1 w - - 0 1")
+        .ok()
+        .unwrap();
+    let limits = Limits {
+        depth: Some(4),
+        time: None,
+    };
+    let result = search(&board, limits);
+    assert_eq!(result.mate_in(), Some(2));
+    assert_eq!(result.pv.len(), 3);
+}
+
+#[test]
+fn wins_material() {
+    // the queen is hanging
+    let (san, score) = best_move("4k3/8/8/3q4/8/8/3R4/4K3 w - - 0 1", 2);
+    assert_eq!(san, "Rxd5");
+    assert!(score > 0);
+}
+
+#[test]
+fn avoids_losing_material() {
+    // taking the pawn loses the queen to the other pawn
+    let (san, _) = best_move("4k3/8/2p5/3p4/8/8/8/3QK3 w - - 0 1", 2);
+    assert_ne!(san, "Qxd5");
+}
+
+#[test]
+fn quiescence() {
+    // at depth 1 the pawn looks free until the recapture is played out
+    let (san, _) = best_move("4k3/8/2p5/3p4/8/8/8/3QK3 w - - 0 1", 1);
+    assert_ne!(san, "Qxd5");
+
+    // a capture that can't be answered is still found
+    let (san, score) = best_move("4k3/8/8/3p4/8/8/8/3QK3 w - - 0 1", 1);
+    assert_eq!(san, "Qxd5");
+    assert!(score > 0);
+}
+
+#[test]
+fn stalemate() {
+    let board = Board::from_fen("7k/5Q2/6K1/8/8/8/8/8 b - - 0 1")
+        .ok()
+        .unwrap();
+    let result = search(&board, Limits::default());
+    assert!(result.best_move.is_none());
+}
+
+#[test]
+fn time_limit() {
+    let board = Board::new();
+    let limits = Limits {
+        depth: None,
+        time: Some(Duration::from_millis(200)),
+    };
+    let result = search(&board, limits);
+    assert!(result.best_move.is_some());
+    assert!(result.time < Duration::from_secs(2));
+}
@@ -0,0 +1,52 @@
+use crate::board::Board;
+use crate::pieces::moves::see;
+
+/// Evaluates the exchange started by a move in standard algebraic notation
+fn exchange(fen: &str, input: &str) -> i32 {
+    let board = Board::from_fen(fen).ok().unwrap();
+    let move_type = board.parse_move(input).ok().unwrap();
+    see(&board, &move_type)
+}
+
+#[test]
+fn undefended() {
+    assert_eq!(
+        exchange("1k1r4/1pp4p/p7/4p3/8/P5P1/1PP4P/2K1R3 w - - 0 1", "Rxe5"),
+        100
+    );
+}
+
+#[test]
+fn defended() {
+    // the queens behind the rook and bishop join in
+    assert_eq!(
+        exchange(
+            "1k1r3q/1ppn3p/p4b2/4p3/8/P2N2P1/1PP1R1BP/2K1Q3 w - - 0 1",
+            "Nxe5"
+        ),
+        -220
+    );
+
+    // taking a defended knight with a pawn still wins material
+    assert_eq!(exchange("4k3/8/2p5/3n4/4P3/8/8/4K3 w - - 0 1", "exd5"), 220);
+}
+
+#[test]
+fn king_recaptures() {
+    // the king can't take back while the other rook is watching
+    assert_eq!(exchange("3k4/3p4/8/8/8/8/3R4/3RK3 w - - 0 1", "Rxd7+"), 100);
+    assert_eq!(exchange("3k4/3p4/8/8/8/8/8/3RK3 w - - 0 1", "Rxd7+"), -400);
+}
+
+#[test]
+fn special_moves() {
+    assert_eq!(exchange("4k3/1P6/8/8/8/8/8/4K3 w - - 0 1", "b8=Q+"), 800);
+    assert_eq!(exchange("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 1", "exd6"), 100);
+    assert_eq!(exchange("4k3/8/8/8/8/8/8/R3K3 w Q - 0 1", "O-O-O"), 0);
+}
+
+#[test]
+fn quiet_moves() {
+    assert_eq!(exchange("4k3/8/4p3/8/8/8/8/3QK3 w - - 0 1", "Qd5"), -900);
+    assert_eq!(exchange("4k3/8/4p3/8/8/8/8/3QK3 w - - 0 1", "Qd4"), 0);
+}
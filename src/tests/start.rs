@@ -0,0 +1,154 @@
+use crate::board::{Board, NUM_COLS};
+use crate::start::Variant;
+use crate::Error;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+fn rank(letters: &str) -> [char; NUM_COLS] {
+    let mut rank = [' '; NUM_COLS];
+    for (x, letter) in letters.chars().enumerate() {
+        rank[x] = letter;
+    }
+    rank
+}
+
+/// Back ranks of the board, as in FEN
+fn back_ranks(board: &Board) -> (String, String) {
+    let fen = board.to_fen();
+    let ranks: Vec<&str> = fen.split(' ').next().unwrap().split('/').collect();
+    (ranks[7].to_string(), ranks[0].to_string())
+}
+
+#[test]
+fn validate() {
+    for variant in Variant::ALL {
+        assert!(variant.validate(&rank("RNBQKBNR")).is_ok());
+        assert!(variant.validate(&rank("BBQNNRKR")).is_ok());
+        assert!(variant.validate(&rank("RNBQKNBR")).is_err());
+        assert!(variant.validate(&rank("RNBQKBNN")).is_err());
+        assert!(variant.validate(&rank("RNBQKBNP")).is_err());
+    }
+
+    // only some variants keep the king between the rooks
+    assert!(Variant::Chess960.validate(&rank("KRBQRBNN")).is_err());
+    assert!(Variant::DoubleFischerRandom
+        .validate(&rank("KRBQRBNN"))
+        .is_err());
+    assert!(Variant::Chess2880.validate(&rank("KRBQRBNN")).is_ok());
+    assert!(Variant::Transcendental.validate(&rank("KRBQRBNN")).is_ok());
+
+    assert_eq!(
+        Variant::Chess960.validate(&rank("♖♘♗♕♔♘♗♖")),
+        Err(Error::InvalidBackRank {
+            value: String::from("RNBQKNBR"),
+            message: String::from("the bishops should be on opposite colours"),
+        })
+    );
+    assert_eq!(
+        Variant::Chess960.validate(&rank("RNBQKBNX")),
+        Err(Error::UnknownPiece { letter: 'X' })
+    );
+}
+
+#[test]
+fn from_back_ranks() {
+    let board = Board::from_back_ranks(&rank("RNBQKBNR"), &rank("RNBQKBNR"), Variant::Chess960)
+        .ok()
+        .unwrap();
+    assert_eq!(board.to_fen(), Board::new().to_fen());
+    assert_eq!(board.grid[0][0].as_ref().unwrap().icon, '♖');
+
+    // each side can have its own pieces, but only if the variant allows it
+    let board = Board::from_back_ranks(
+        &rank("RNBQKBNR"),
+        &rank("BBQNNRKR"),
+        Variant::DoubleFischerRandom,
+    )
+    .ok()
+    .unwrap();
+    assert_eq!(
+        board.to_fen(),
+        "bbqnnrkr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1"
+    );
+    let error =
+        Board::from_back_ranks(&rank("RNBQKBNR"), &rank("BBQNNRKR"), Variant::Chess2880).err();
+    assert_eq!(
+        error.map(|error| error.to_string()),
+        Some(String::from(
+            "invalid back rank `BBQNNRKR`, both sides should start with RNBQKBNR"
+        ))
+    );
+}
+
+#[test]
+fn castling() {
+    // transcendental kings can't castle at all
+    let board = Board::from_back_ranks(
+        &rank("KRBQRBNN"),
+        &rank("NRBKRBNQ"),
+        Variant::Transcendental,
+    )
+    .ok()
+    .unwrap();
+    assert!(board.state.castling.is_empty());
+    assert!(board.to_fen().contains(" w - - "));
+
+    // otherwise the outermost rook on each side of the king can castle
+    let board = Board::from_back_ranks(&rank("KRBQRBNN"), &rank("KRBQRBNN"), Variant::Chess2880)
+        .ok()
+        .unwrap();
+    assert_eq!(board.state.castling_files(true), vec![4]);
+    assert!(board.to_fen().contains(" w Kk - "));
+
+    let read = Board::from_fen(&board.to_fen()).ok().unwrap();
+    assert_eq!(read.state.castling_files(true), vec![4]);
+}
+
+#[test]
+fn new_variant() {
+    let generate =
+        |variant: Variant, seed: u64| Board::new_variant(variant, &mut StdRng::seed_from_u64(seed));
+
+    for variant in Variant::ALL {
+        let mut mirrored = true;
+        for seed in 0..50 {
+            let board = generate(variant, seed);
+            let (white, black) = back_ranks(&board);
+            let black = black.to_ascii_uppercase();
+            assert!(variant.validate(&rank(&white)).is_ok());
+            assert!(variant.validate(&rank(&black)).is_ok());
+            mirrored &= white == black;
+        }
+        assert_eq!(mirrored, !variant.independent());
+
+        // seeds give the same position each time
+        assert_eq!(generate(variant, 7).to_fen(), generate(variant, 7).to_fen());
+    }
+
+    // chess960 draws the same positions as a random chess960 board
+    for seed in 0..10 {
+        assert_eq!(
+            generate(Variant::Chess960, seed).to_fen(),
+            Board::new_random(&mut StdRng::seed_from_u64(seed)).to_fen()
+        );
+    }
+
+    // the king is sometimes outside the rooks when it doesn't have to be between them
+    let outside = |variant: Variant| {
+        (0..50).any(|seed| {
+            let (white, _) = back_ranks(&generate(variant, seed));
+            let king = white.find('K').unwrap();
+            !(white.find('R').unwrap() < king && king < white.rfind('R').unwrap())
+        })
+    };
+    assert!(outside(Variant::Chess2880));
+    assert!(outside(Variant::Transcendental));
+}
+
+#[test]
+fn from_name() {
+    for variant in Variant::ALL {
+        assert_eq!(Variant::from_name(variant.name()), Some(variant));
+    }
+    assert_eq!(Variant::from_name("Crazyhouse"), None);
+}
@@ -0,0 +1,78 @@
+use super::square;
+use crate::board::Board;
+
+#[test]
+fn side_to_move() {
+    let mut board = Board::new();
+    assert!(board.state.white);
+    board.make_move("e4");
+    assert!(!board.state.white);
+    board.make_move("e5");
+    assert!(board.state.white);
+    assert_eq!(board.state.fullmove_number, 2);
+}
+
+#[test]
+fn en_passant_target() {
+    let mut board = Board::new();
+    board.make_move("e4");
+    assert!(board.state.en_passant == Some(square("e3")));
+    board.make_move("Nf6");
+    assert!(board.state.en_passant.is_none());
+}
+
+#[test]
+fn en_passant_after_check() {
+    // the double pawn move gives check, but can still be taken en passant
+    let mut board = Board::from_fen("8/8/8/2k5/4p3/8/3P4/4K3 w - - 0 1")
+        .ok()
+        .unwrap();
+    assert!(board.make_move("d4"));
+    assert_eq!(board.history.last().unwrap(), "d4+");
+    assert!(board.make_move("exd3"));
+    assert!(board.grid[3][3].is_none());
+}
+
+#[test]
+fn castling_rights() {
+    let mut board = Board::new();
+    assert_eq!(board.state.castling.len(), 4);
+    assert_eq!(board.state.castling_files(true), vec![0, 7]);
+
+    // moving a rook only gives up that rook's rights
+    board.make_move("h4");
+    board.make_move("a5");
+    board.make_move("Rh3");
+    assert_eq!(board.state.castling_files(true), vec![0]);
+
+    // moving the king gives up both
+    board.make_move("Ra6");
+    assert_eq!(board.state.castling_files(false), vec![7]);
+    board.make_move("e3");
+    board.make_move("e6");
+    board.make_move("Ke2");
+    assert!(board.state.castling_files(true).is_empty());
+}
+
+#[test]
+fn captured_rook() {
+    let mut board = Board::from_fen("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1")
+        .ok()
+        .unwrap();
+    assert!(board.make_move("Rxa8+"));
+    assert_eq!(board.state.castling_files(false), vec![7]);
+    assert_eq!(board.to_fen(), "R3k2r/8/8/8/8/8/8/4K2R b Kk - 0 1");
+}
+
+#[test]
+fn undo_restores_state() {
+    let mut board = Board::new();
+    let state = board.state.clone();
+    board.make_move("e4");
+    board.make_move("e5");
+    board.make_move("Ke2");
+    assert!(board.undo_move());
+    assert!(board.undo_move());
+    assert!(board.undo_move());
+    assert!(board.state == state);
+}
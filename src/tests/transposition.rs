@@ -0,0 +1,95 @@
+use crate::board::Board;
+use crate::search::{Limits, Search, MATE};
+use crate::transposition::{from_table, to_table, Bound, TranspositionTable};
+use std::sync::{Arc, Mutex};
+
+#[test]
+fn size() {
+    let small = TranspositionTable::new(1);
+    let large = TranspositionTable::new(4);
+    assert!(!small.is_empty());
+    assert_eq!(large.len(), small.len() * 4);
+}
+
+#[test]
+fn probe() {
+    let mut table = TranspositionTable::new(1);
+    let key = 0x1234_5678_9abc_def0;
+    assert!(table.probe(key).is_none());
+
+    table.store(key, None, 3, Bound::Upper, -50, 0);
+    let entry = table.probe(key).unwrap();
+    assert_eq!(entry.depth, 3);
+    assert_eq!(entry.bound, Bound::Upper);
+    assert_eq!(entry.score, -50);
+
+    // positions sharing a slot aren't mistaken for each other
+    assert!(table.probe(key + table.len() as u64).is_none());
+
+    table.clear();
+    assert!(table.probe(key).is_none());
+}
+
+#[test]
+fn replace_by_depth() {
+    let mut table = TranspositionTable::new(1);
+    let key = 42;
+    let other = key + table.len() as u64;
+
+    table.store(key, None, 5, Bound::Exact, 10, 0);
+    table.store(other, None, 2, Bound::Exact, 20, 0);
+    assert_eq!(table.probe(key).unwrap().score, 10);
+    assert!(table.probe(other).is_none());
+
+    table.store(other, None, 5, Bound::Exact, 20, 0);
+    assert_eq!(table.probe(other).unwrap().score, 20);
+
+    // anything left from an earlier search can be replaced
+    table.new_search();
+    table.store(key, None, 1, Bound::Exact, 30, 0);
+    assert_eq!(table.probe(key).unwrap().score, 30);
+}
+
+#[test]
+fn keeps_best_move() {
+    let board = Board::new();
+    let best_move = board.legal_moves().remove(0);
+    let mut table = TranspositionTable::new(1);
+    table.store(board.hash, Some(best_move.clone()), 2, Bound::Lower, 0, 0);
+    table.store(board.hash, None, 3, Bound::Upper, 0, 0);
+    assert!(table.probe(board.hash).unwrap().best_move == Some(best_move));
+}
+
+#[test]
+fn mate_scores() {
+    // mate in 3 plies from the root, found 2 plies in, is mate in 1 ply from the stored position
+    assert_eq!(to_table(MATE - 3, 2), MATE - 1);
+    assert_eq!(from_table(MATE - 1, 2), MATE - 3);
+    assert_eq!(to_table(-MATE + 3, 2), -MATE + 1);
+    assert_eq!(from_table(to_table(-MATE + 5, 4), 1), -MATE + 2);
+
+    // other scores are left alone
+    assert_eq!(to_table(150, 7), 150);
+    assert_eq!(from_table(-150, 7), -150);
+}
+
+#[test]
+fn shared_between_searches() {
+    let board = Board::from_fen("7k/8/8/8/8/8/R7/1R4K1 w - - 0 1")
+        .ok()
+        .unwrap();
+    let table = Arc::new(Mutex::new(TranspositionTable::new(1)));
+    let limits = Limits {
+        depth: Some(4),
+        time: None,
+    };
+
+    let first = Search::with_table(limits.clone(), table.clone()).run(&board, |_| ());
+    assert!(table.lock().unwrap().probe(board.hash).is_some());
+
+    // the second search finds the same mate in fewer nodes
+    let second = Search::with_table(limits, table.clone()).run(&board, |_| ());
+    assert_eq!(second.mate_in(), Some(2));
+    assert_eq!(second.score, first.score);
+    assert!(second.nodes < first.nodes);
+}
@@ -0,0 +1,210 @@
+use crate::book::Book;
+use crate::pgn::Pgn;
+use crate::uci::Uci;
+use std::io::{self, Write};
+use std::sync::{Arc, Mutex};
+
+/// Output shared with the engine so replies from the search thread can be read back
+#[derive(Clone, Default)]
+struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+impl Write for Buffer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Buffer {
+    /// Takes everything written so far
+    fn take(&self) -> String {
+        let bytes: Vec<u8> = self.0.lock().unwrap().drain(..).collect();
+        String::from_utf8(bytes).unwrap()
+    }
+}
+
+/// Sends commands to the engine, waits for any search and collects the replies
+fn send(uci: &mut Uci, buffer: &Buffer, commands: &[&str]) -> String {
+    for command in commands {
+        uci.handle(command).ok().unwrap();
+    }
+    uci.wait();
+    buffer.take()
+}
+
+fn engine() -> (Uci, Buffer) {
+    let buffer = Buffer::default();
+    (Uci::new(buffer.clone()), buffer)
+}
+
+#[test]
+fn handshake() {
+    let (mut uci, buffer) = engine();
+    let reply = send(&mut uci, &buffer, &["uci", "isready"]);
+    assert!(reply.contains("option name UCI_Chess960 type check default false\n"));
+    assert!(reply.contains("option name Hash type spin default 16 min 1 max 4096\n"));
+    assert!(reply.ends_with("uciok\nreadyok\n"));
+}
+
+#[test]
+fn hash_option() {
+    let (mut uci, buffer) = engine();
+    let default = uci.table.lock().unwrap().len();
+    send(&mut uci, &buffer, &["setoption name Hash value 1"]);
+    assert_eq!(uci.table.lock().unwrap().len(), default / 16);
+
+    // a search still works after resizing
+    let reply = send(&mut uci, &buffer, &["position startpos", "go depth 2"]);
+    assert!(reply.contains("bestmove "));
+}
+
+#[test]
+fn quit() {
+    let (mut uci, _) = engine();
+    assert!(uci.handle("isready").ok().unwrap());
+    assert!(!uci.handle("quit").ok().unwrap());
+}
+
+#[test]
+fn startpos_moves() {
+    let (mut uci, buffer) = engine();
+    send(
+        &mut uci,
+        &buffer,
+        &["position startpos moves e2e4 e7e5 g1f3 b8c6 f1c4 g8f6 e1g1"],
+    );
+    assert!(!uci.board.state.white);
+    assert_eq!(
+        uci.board.to_fen(),
+        "r1bqkb1r/pppp1ppp/2n2n2/4p3/2B1P3/5N2/PPPP1PPP/RNBQ1RK1 b kq - 5 4"
+    );
+}
+
+#[test]
+fn fen_moves() {
+    let (mut uci, buffer) = engine();
+    send(
+        &mut uci,
+        &buffer,
+        &["position fen 4k3/1P6/8/8/8/8/8/4K3 w - - 0 1 moves b7b8n"],
+    );
+    assert_eq!(uci.board.to_fen(), "1N2k3/8/8/8/8/8/8/4K3 b - - 0 1");
+}
+
+#[test]
+fn illegal_move() {
+    let (mut uci, buffer) = engine();
+    let reply = send(&mut uci, &buffer, &["position startpos moves e2e5"]);
+    assert_eq!(reply, "info string illegal move e2e5\n");
+    assert!(uci.board.state.white);
+}
+
+#[test]
+fn chess960_castle() {
+    let fen = "fen rk2r3/8/8/8/8/8/8/RK2R3 w EAea - 0 1";
+    let (mut uci, buffer) = engine();
+    send(
+        &mut uci,
+        &buffer,
+        &[&format!("position {} moves b1a1", fen)],
+    );
+    assert!(uci.board.state.white);
+
+    send(
+        &mut uci,
+        &buffer,
+        &[
+            "setoption name UCI_Chess960 value true",
+            &format!("position {} moves b1a1", fen),
+        ],
+    );
+    assert!(!uci.board.state.white);
+    assert_eq!(uci.board.to_fen(), "rk2r3/8/8/8/8/8/8/2KRR3 b kq - 1 1");
+}
+
+#[test]
+fn own_book() {
+    let games = [Pgn::parse("1. d4 d5 1-0").ok().unwrap()];
+    let path = std::env::temp_dir().join(format!("chess-uci-{}.bin", std::process::id()));
+    let path = path.to_str().unwrap();
+    Book::build(&games, 1).save(path).ok().unwrap();
+
+    let (mut uci, buffer) = engine();
+    let reply = send(
+        &mut uci,
+        &buffer,
+        &[
+            &format!("setoption name BookFile value {}", path),
+            "setoption name OwnBook value true",
+            "position startpos",
+            "go depth 1",
+        ],
+    );
+    assert_eq!(reply, "bestmove d2d4\n");
+
+    // searches once out of the book
+    let reply = send(
+        &mut uci,
+        &buffer,
+        &["position startpos moves d2d4", "go depth 1"],
+    );
+    assert!(reply.starts_with("info depth 1"));
+    let _ = std::fs::remove_file(path);
+
+    let reply = send(
+        &mut uci,
+        &buffer,
+        &["setoption name BookFile value missing.bin"],
+    );
+    assert!(reply.starts_with("info string could not open book"));
+}
+
+#[test]
+fn go() {
+    let (mut uci, buffer) = engine();
+    let reply = send(&mut uci, &buffer, &["position startpos", "go depth 1"]);
+    let best = reply
+        .lines()
+        .last()
+        .unwrap()
+        .strip_prefix("bestmove ")
+        .unwrap();
+    assert!(uci.board.parse_uci_move(best, false).is_ok());
+
+    let reply = send(
+        &mut uci,
+        &buffer,
+        &["position fen 7k/5Q2/6K1/8/8/8/8/8 b - - 0 1", "go depth 1"],
+    );
+    assert_eq!(reply, "bestmove 0000\n");
+}
+
+#[test]
+fn go_mate() {
+    let (mut uci, buffer) = engine();
+    let reply = send(
+        &mut uci,
+        &buffer,
+        &[
+            "position fen 6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1",
+            "go depth 3",
+        ],
+    );
+    assert!(reply.contains("score mate 1 "));
+    assert!(reply.ends_with("bestmove a1a8\n"));
+}
+
+#[test]
+fn stop() {
+    let (mut uci, buffer) = engine();
+    let reply = send(
+        &mut uci,
+        &buffer,
+        &["position startpos", "go infinite", "stop"],
+    );
+    assert!(reply.ends_with('\n'));
+    assert!(reply.lines().last().unwrap().starts_with("bestmove "));
+}
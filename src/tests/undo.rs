@@ -0,0 +1,92 @@
+use crate::board::Board;
+
+/// Plays moves from a position, then checks undoing them all gets back to where it started
+fn round_trip(fen: &str, moves: &[&str]) {
+    let mut board = Board::from_fen(fen).ok().unwrap();
+    let mut fens = vec![board.to_fen()];
+    for input in moves {
+        assert!(board.make_move(input));
+        fens.push(board.to_fen());
+    }
+
+    for fen in fens.iter().rev().skip(1) {
+        assert!(board.undo_move());
+        assert_eq!(&board.to_fen(), fen);
+    }
+    assert!(!board.undo_move());
+
+    for fen in fens.iter().skip(1) {
+        assert!(board.redo());
+        assert_eq!(&board.to_fen(), fen);
+    }
+    assert!(!board.redo());
+}
+
+#[test]
+fn normal_moves() {
+    let fen = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+    round_trip(fen, &["e4", "d5", "exd5", "Qxd5", "Nc3", "Qa5"]);
+}
+
+#[test]
+fn promotion() {
+    round_trip(
+        "1n2k3/P7/8/8/8/8/8/4K3 w - - 0 1",
+        &["axb8=N", "Kf7", "Nc6"],
+    );
+}
+
+#[test]
+fn castling() {
+    let fen = "r3k2r/pppppppp/8/8/8/8/PPPPPPPP/R3K2R w KQkq - 0 1";
+    round_trip(fen, &["O-O", "O-O-O", "Rfe1", "Kb8"]);
+}
+
+#[test]
+fn chess960_castling() {
+    // the king and rook swap places
+    round_trip("4k3/8/8/8/8/8/8/5KR1 w G - 0 1", &["O-O", "Kd7", "Kh1"]);
+}
+
+#[test]
+fn en_passant() {
+    let fen = "4k3/4p3/8/3P4/8/8/8/4K3 b - - 0 1";
+    round_trip(fen, &["e5", "dxe6", "Kf8", "e7+", "Kxe7"]);
+}
+
+#[test]
+fn restores_counters() {
+    let mut board = Board::new();
+    board.make_move("Nf3");
+    board.make_move("Nf6");
+    board.make_move("e4");
+    assert!(board.undo_move());
+    assert!(board.undo_move());
+    assert_eq!(board.state.halfmove_clock, 1);
+    assert_eq!(board.state.fullmove_number, 1);
+    assert_eq!(board.history, vec!["Nf3"]);
+    assert_eq!(board.positions.len(), 2);
+
+    // the king and rook can castle again once their moves are taken back
+    let mut board = Board::from_fen("4k3/8/8/8/8/8/8/4K2R w K - 0 1")
+        .ok()
+        .unwrap();
+    board.make_move("Kf1");
+    board.make_move("Kd7");
+    board.make_move("Ke1");
+    assert!(!board.make_move("O-O"));
+    assert!(board.undo_move());
+    assert!(board.undo_move());
+    assert!(board.undo_move());
+    assert!(board.make_move("O-O"));
+}
+
+#[test]
+fn new_move_clears_redo() {
+    let mut board = Board::new();
+    board.make_move("e4");
+    assert!(board.undo_move());
+    board.make_move("d4");
+    assert!(!board.redo());
+    assert_eq!(board.history, vec!["d4"]);
+}
@@ -0,0 +1,149 @@
+use super::from_fen;
+use crate::board::Board;
+use crate::zobrist;
+
+/// Makes each move, checking that the updated key matches one worked out from scratch
+fn play(board: &mut Board, moves: &[&str]) {
+    for input in moves {
+        assert!(board.make_move(input), "{}", input);
+        assert_eq!(board.hash, zobrist::hash(board), "{}", input);
+    }
+}
+
+#[test]
+fn matches_fen() {
+    assert_eq!(
+        Board::new().hash,
+        from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1").hash
+    );
+
+    let mut board = Board::new();
+    play(&mut board, &["e4", "c5", "Nf3"]);
+    assert_eq!(
+        board.hash,
+        from_fen("rnbqkbnr/pp1ppppp/8/2p5/4P3/5N2/PPPP1PPP/RNBQKB1R b KQkq - 1 2").hash
+    );
+}
+
+#[test]
+fn incremental() {
+    // castling, en passant, promotion and captures
+    let mut board = Board::new();
+    play(
+        &mut board,
+        &[
+            "e4", "d5", "e5", "f5", "exf6", "Nc6", "fxg7", "Be6", "gxh8=Q", "Qd7", "Nf3", "O-O-O",
+            "Be2", "d4", "O-O", "Kb8",
+        ],
+    );
+
+    let mut board = from_fen("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1");
+    play(&mut board, &["Rxa8+", "Kf7", "Rh7+", "Ke6", "R7xh8"]);
+}
+
+#[test]
+fn transpositions() {
+    let mut board = Board::new();
+    let start = board.hash;
+    play(&mut board, &["Nf3", "Nf6", "Ng1", "Ng8"]);
+    assert_eq!(board.hash, start);
+
+    let mut other = Board::new();
+    play(&mut other, &["d4", "Nf6", "c4"]);
+    play(&mut board, &["c4", "Nf6", "d4"]);
+    assert_eq!(board.hash, other.hash);
+}
+
+#[test]
+fn state() {
+    // side to move
+    let white = from_fen("4k3/8/8/8/8/8/8/4K3 w - - 0 1");
+    let black = from_fen("4k3/8/8/8/8/8/8/4K3 b - - 0 1");
+    assert!(white.hash != black.hash);
+
+    // castling rights, even after the king returns
+    let mut board = Board::new();
+    play(&mut board, &["e4", "e5", "Ke2", "Ke7", "Ke1", "Ke8"]);
+    assert!(
+        board.hash != from_fen("rnbqkbnr/pppp1ppp/8/4p3/4P3/8/PPPP1PPP/RNBQKBNR w KQkq - 4 5").hash
+    );
+    assert_eq!(
+        board.hash,
+        from_fen("rnbqkbnr/pppp1ppp/8/4p3/4P3/8/PPPP1PPP/RNBQKBNR w - - 4 5").hash
+    );
+}
+
+#[test]
+fn en_passant() {
+    // only counts if a pawn could take
+    let mut board = Board::new();
+    play(&mut board, &["e4"]);
+    assert_eq!(
+        board.hash,
+        from_fen("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 1").hash
+    );
+
+    let with = from_fen("4k3/8/8/8/3pP3/8/8/4K3 b - e3 0 1");
+    let without = from_fen("4k3/8/8/8/3pP3/8/8/4K3 b - - 0 1");
+    assert!(with.hash != without.hash);
+}
+
+#[test]
+fn undo() {
+    let mut board = Board::new();
+    let start = board.hash;
+    play(&mut board, &["e4", "d5", "exd5", "c5", "dxc6"]);
+    let end = board.hash;
+    while board.undo_move() {
+        assert_eq!(board.hash, zobrist::hash(&board));
+    }
+    assert_eq!(board.hash, start);
+
+    while board.redo() {}
+    assert_eq!(board.hash, end);
+}
+
+#[test]
+fn use_keys() {
+    // keys can't be swapped once boards have been hashed
+    Board::new();
+    assert!(!zobrist::use_keys(&[0; 10]));
+    assert!(!zobrist::use_keys(&[0; zobrist::NUM_KEYS]));
+}
+
+#[test]
+fn parse_keys() {
+    assert_eq!(
+        zobrist::parse_keys("0x9D39247E33776D41, 0x2AF7398005AAA5C7,\n  44DB015024623547"),
+        Some(vec![
+            0x9D39247E33776D41,
+            0x2AF7398005AAA5C7,
+            0x44DB015024623547
+        ])
+    );
+    assert_eq!(zobrist::parse_keys("0x12, zz"), None);
+}
+
+#[test]
+fn polyglot() {
+    // keys from Polyglot's book format, checked against its published examples
+    let mut board = Board::new();
+    assert_eq!(board.hash, 0x463b96181691fc9c);
+    for (input, key) in [
+        ("e4", 0x823c9b50fd114196),
+        ("d5", 0x0756b94461c50fb0),
+        ("e5", 0x662fafb965db29d4),
+        ("f5", 0x22a48b5a8e47ff78),
+        ("Ke2", 0x652a607ca3f242c1),
+        ("Kf7", 0x00fdd303c946bdd9),
+    ] {
+        play(&mut board, &[input]);
+        assert_eq!(board.hash, key, "{}", input);
+    }
+
+    let mut board = Board::new();
+    play(&mut board, &["a4", "b5", "h4", "b4", "c4"]);
+    assert_eq!(board.hash, 0x3c8123ea7b067637);
+    play(&mut board, &["bxc3", "Ra3"]);
+    assert_eq!(board.hash, 0x5c3f9b829b279560);
+}
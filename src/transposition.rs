@@ -0,0 +1,151 @@
+use crate::pieces::moves::MoveType;
+use crate::search::MATE_THRESHOLD;
+use std::mem;
+
+/// Size used unless one is given, in megabytes
+pub const DEFAULT_SIZE: usize = 16;
+
+/// How a stored score relates to the real score of the position
+/// * `Exact` - every move was searched without a cutoff
+/// * `Lower` - a move was good enough for a cutoff, so the real score could be higher
+/// * `Upper` - no move reached alpha, so the real score could be lower
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Bound {
+    Exact,
+    Lower,
+    Upper,
+}
+
+/// What was found the last time a position was searched
+/// * `key` - full hash of the position, since many positions share a slot
+/// * `best_move` - `None` if every move failed low
+/// * `depth` - plies searched below the position
+/// * `score` - mates are counted from this position rather than the root, see [to_table]
+/// * `age` - search that stored the entry, so old entries get replaced first
+#[derive(Clone)]
+pub struct Entry {
+    pub key: u64,
+    pub best_move: Option<MoveType>,
+    pub depth: usize,
+    pub bound: Bound,
+    pub score: i32,
+    age: u8,
+}
+
+/// Fixed size store of searched positions, indexed by Zobrist hash
+/// * deeper results are kept over shallower ones from the same search
+pub struct TranspositionTable {
+    entries: Vec<Option<Entry>>,
+    age: u8,
+}
+
+impl TranspositionTable {
+    /// Creates a table taking up about `megabytes` of memory
+    pub fn new(megabytes: usize) -> TranspositionTable {
+        let length = (megabytes.max(1) << 20) / mem::size_of::<Option<Entry>>();
+        TranspositionTable {
+            entries: vec![None; length],
+            age: 0,
+        }
+    }
+
+    /// Changes the size, which clears the table
+    pub fn resize(&mut self, megabytes: usize) {
+        *self = Self::new(megabytes);
+    }
+
+    /// Forgets every position, e.g. for a new game
+    pub fn clear(&mut self) {
+        self.entries.iter_mut().for_each(|entry| *entry = None);
+        self.age = 0;
+    }
+
+    /// Number of entries that fit in the table
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Marks the start of a new search, so entries from earlier ones can be replaced
+    pub fn new_search(&mut self) {
+        self.age = self.age.wrapping_add(1);
+    }
+
+    fn index(&self, key: u64) -> usize {
+        (key % self.entries.len() as u64) as usize
+    }
+
+    /// Looks up a position
+    /// * returns `None` if it hasn't been stored or was replaced
+    pub fn probe(&self, key: u64) -> Option<&Entry> {
+        match &self.entries[self.index(key)] {
+            Some(entry) if entry.key == key => Some(entry),
+            _ => None,
+        }
+    }
+
+    /// Stores a search result, unless a deeper one from the same search is in the slot
+    /// * `ply` - distance from the root, for adjusting mate scores
+    pub fn store(
+        &mut self,
+        key: u64,
+        best_move: Option<MoveType>,
+        depth: usize,
+        bound: Bound,
+        score: i32,
+        ply: usize,
+    ) {
+        let index = self.index(key);
+        let replace = match &self.entries[index] {
+            Some(entry) => entry.key == key || entry.age != self.age || depth >= entry.depth,
+            None => true,
+        };
+        if !replace {
+            return;
+        }
+
+        // keep the old move if the new search didn't find one
+        let best_move = match (best_move, &self.entries[index]) {
+            (Some(best_move), _) => Some(best_move),
+            (None, Some(entry)) if entry.key == key => entry.best_move.clone(),
+            (None, _) => None,
+        };
+
+        self.entries[index] = Some(Entry {
+            key,
+            best_move,
+            depth,
+            bound,
+            score: to_table(score, ply),
+            age: self.age,
+        });
+    }
+}
+
+/// Counts mates from the position being stored instead of from the root, since the same position
+/// can be reached at different plies
+pub fn to_table(score: i32, ply: usize) -> i32 {
+    if score >= MATE_THRESHOLD {
+        return score + ply as i32;
+    }
+    if score <= -MATE_THRESHOLD {
+        return score - ply as i32;
+    }
+
+    score
+}
+
+/// Counts mates from the root again, undoing [to_table]
+pub fn from_table(score: i32, ply: usize) -> i32 {
+    if score >= MATE_THRESHOLD {
+        return score - ply as i32;
+    }
+    if score <= -MATE_THRESHOLD {
+        return score + ply as i32;
+    }
+
+    score
+}
@@ -0,0 +1,361 @@
+use crate::board::{Board, KINGSIDE_CASTLE, NUM_ROWS, QUEENSIDE_CASTLE};
+use crate::book::Book;
+use crate::clock;
+use crate::coordinate::Coordinate;
+use crate::endgame::Endgames;
+use crate::pieces::moves::MoveType;
+use crate::pieces::Id;
+use crate::search::{Limits, Search};
+use crate::transposition::{self, TranspositionTable};
+use crate::{Error, MoveError};
+use std::io::{self, Write};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+const NAME: &str = "chess";
+const AUTHOR: &str = "leslieyip02";
+
+// largest transposition table a GUI can ask for, in megabytes
+const MAX_HASH: usize = 4096;
+
+impl MoveType {
+    /// Writes the move in long algebraic notation, e.g. `e2e4` or `e7e8q`
+    /// * castling is written as the king's move, or as the king taking its own rook in chess960
+    pub fn to_uci(&self, white: bool, chess960: bool) -> String {
+        match self {
+            MoveType::Normal {
+                piece,
+                target,
+                promotion,
+            } => {
+                let promotion = match promotion.map(Id::from_char) {
+                    Some(Ok(id)) => id.to_char().map(|c| c.to_ascii_lowercase()),
+                    _ => None,
+                };
+
+                let mut uci = piece.position.to_alphanumeric() + &target.to_alphanumeric();
+                uci.extend(promotion);
+                uci
+            }
+            MoveType::Castle {
+                king_x,
+                rook_x,
+                kingside,
+            } => {
+                let y = if white { 0 } else { NUM_ROWS - 1 };
+                let target_x = match (chess960, kingside) {
+                    (true, _) => *rook_x,
+                    (false, true) => KINGSIDE_CASTLE[0],
+                    (false, false) => QUEENSIDE_CASTLE[0],
+                };
+
+                let from = Coordinate { x: *king_x, y };
+                let target = Coordinate { x: target_x, y };
+                from.to_alphanumeric() + &target.to_alphanumeric()
+            }
+            MoveType::EnPassant { from, target, .. } => {
+                from.to_alphanumeric() + &target.to_alphanumeric()
+            }
+        }
+    }
+}
+
+impl Board {
+    /// Finds the legal move written in long algebraic notation
+    pub fn parse_uci_move(&self, input: &str, chess960: bool) -> Result<MoveType, Error> {
+        for move_type in self.legal_moves() {
+            if move_type.to_uci(self.state.white, chess960) == input {
+                return Ok(move_type);
+            }
+        }
+
+        Err(MoveError::NotLegal.into())
+    }
+}
+
+/// State kept between commands from a GUI speaking the Universal Chess Interface
+/// * `board` - current position, including the side to move
+/// * `chess960` - whether castling is written as the king taking its rook
+/// * `table` - transposition table kept between searches, sized by the `Hash` option
+/// * `book` - opening book set by the `BookFile` option, only used if `own_book` is set
+/// * `endgames` - generated distance to mate tables in the directory set by `EndgamePath`
+/// * `output` - where replies are written, shared with the search thread
+pub struct Uci {
+    pub board: Board,
+    pub chess960: bool,
+    pub table: Arc<Mutex<TranspositionTable>>,
+    pub book: Option<Book>,
+    pub own_book: bool,
+    pub endgames: Option<Arc<Endgames>>,
+    output: Arc<Mutex<dyn Write + Send>>,
+    stop: Arc<AtomicBool>,
+    thinking: Option<JoinHandle<()>>,
+}
+
+impl Uci {
+    pub fn new(output: impl Write + Send + 'static) -> Uci {
+        Uci {
+            board: Board::new(),
+            chess960: false,
+            table: Arc::new(Mutex::new(TranspositionTable::new(
+                transposition::DEFAULT_SIZE,
+            ))),
+            book: None,
+            own_book: false,
+            endgames: None,
+            output: Arc::new(Mutex::new(output)),
+            stop: Arc::new(AtomicBool::new(false)),
+            thinking: None,
+        }
+    }
+
+    /// Responds to a single line of input
+    /// * returns `false` once the GUI asks to quit
+    pub fn handle(&mut self, line: &str) -> io::Result<bool> {
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+        let (command, args) = match tokens.split_first() {
+            Some((command, args)) => (*command, args),
+            None => return Ok(true),
+        };
+
+        match command {
+            "uci" => {
+                self.write(&format!("id name {}", NAME))?;
+                self.write(&format!("id author {}", AUTHOR))?;
+                self.write(&format!(
+                    "option name Hash type spin default {} min 1 max {}",
+                    transposition::DEFAULT_SIZE,
+                    MAX_HASH
+                ))?;
+                self.write("option name OwnBook type check default false")?;
+                self.write("option name BookFile type string default <empty>")?;
+                self.write("option name EndgamePath type string default <empty>")?;
+                self.write("option name UCI_Chess960 type check default false")?;
+                self.write("uciok")?;
+            }
+            "isready" => self.write("readyok")?,
+            "setoption" => {
+                self.wait();
+                match self.set_option(args) {
+                    Ok(()) => (),
+                    Err(message) => self.write(&format!("info string {}", message))?,
+                }
+            }
+            "ucinewgame" => {
+                self.wait();
+                self.table().clear();
+                self.board = Board::new();
+            }
+            "position" => {
+                self.wait();
+                match self.position(args) {
+                    Ok(()) => (),
+                    Err(message) => self.write(&format!("info string {}", message))?,
+                }
+            }
+            "go" => self.go(args)?,
+            "stop" => self.stop.store(true, Ordering::Relaxed),
+            "quit" => {
+                self.stop.store(true, Ordering::Relaxed);
+                self.wait();
+                return Ok(false);
+            }
+            _ => self.write(&format!("info string unknown command {}", command))?,
+        }
+
+        Ok(true)
+    }
+
+    /// Waits for the current search to send its best move
+    pub fn wait(&mut self) {
+        if let Some(thinking) = self.thinking.take() {
+            let _ = thinking.join();
+        }
+    }
+
+    /// Locks the transposition table, which only the search thread holds otherwise
+    fn table(&self) -> MutexGuard<'_, TranspositionTable> {
+        match self.table.lock() {
+            Ok(table) => table,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+
+    fn write(&self, line: &str) -> io::Result<()> {
+        send(&self.output, line)
+    }
+
+    /// Handles `setoption name <name> value <value>`
+    fn set_option(&mut self, args: &[&str]) -> Result<(), String> {
+        match args {
+            ["name", "UCI_Chess960", "value", value] => self.chess960 = *value == "true",
+            ["name", "Hash", "value", value] => {
+                if let Ok(megabytes) = value.parse::<usize>() {
+                    self.table().resize(megabytes.clamp(1, MAX_HASH))
+                }
+            }
+            ["name", "OwnBook", "value", value] => self.own_book = *value == "true",
+            ["name", "BookFile", "value", path @ ..] => {
+                let path = path.join(" ");
+                self.book = match path.as_str() {
+                    "" | "<empty>" => None,
+                    _ => match Book::open(&path) {
+                        Ok(book) => Some(book),
+                        Err(error) => return Err(format!("could not open book: {}", error)),
+                    },
+                };
+            }
+            ["name", "EndgamePath", "value", path @ ..] => {
+                let path = path.join(" ");
+                self.endgames = match path.as_str() {
+                    "" | "<empty>" => None,
+                    _ => match Endgames::open(Path::new(&path)) {
+                        Ok(endgames) => Some(Arc::new(endgames)),
+                        Err(error) => return Err(format!("could not open endgames: {}", error)),
+                    },
+                };
+            }
+            _ => (),
+        }
+
+        Ok(())
+    }
+
+    /// Handles `position startpos` or `position fen <fen>`, followed by `moves ...`
+    fn position(&mut self, args: &[&str]) -> Result<(), String> {
+        let split = args.iter().position(|arg| *arg == "moves");
+        let (setup, moves) = match split {
+            Some(i) => (&args[..i], &args[i + 1..]),
+            None => (args, &args[args.len()..]),
+        };
+
+        let mut board = match setup {
+            ["startpos"] => Board::new(),
+            ["fen", fen @ ..] => match Board::from_fen(&fen.join(" ")) {
+                Ok(position) => position,
+                Err(_) => return Err(format!("invalid fen {}", fen.join(" "))),
+            },
+            _ => return Err(String::from("expected startpos or fen")),
+        };
+
+        for input in moves {
+            match board.parse_uci_move(input, self.chess960) {
+                Ok(move_type) => board.play(&move_type),
+                Err(_) => return Err(format!("illegal move {}", input)),
+            }
+        }
+
+        self.board = board;
+        Ok(())
+    }
+
+    /// Reads the search limits given to `go`
+    /// * a share of the remaining clock time is used when no fixed limit is given
+    fn limits(&self, args: &[&str]) -> Limits {
+        let mut limits = Limits::default();
+        let mut clock: Option<u64> = None;
+        let mut increment: u64 = 0;
+        let mut moves_to_go: Option<u64> = None;
+
+        for pair in args.windows(2) {
+            let value = match pair[1].parse::<u64>() {
+                Ok(value) => value,
+                Err(_) => continue,
+            };
+
+            match (pair[0], self.board.state.white) {
+                ("depth", _) => limits.depth = Some(value as usize),
+                ("movetime", _) => limits.time = Some(Duration::from_millis(value)),
+                ("wtime", true) | ("btime", false) => clock = Some(value),
+                ("winc", true) | ("binc", false) => increment = value,
+                ("movestogo", _) => moves_to_go = Some(value),
+                _ => (),
+            }
+        }
+
+        if args.contains(&"infinite") {
+            return Limits::default();
+        }
+
+        if limits.time.is_none() {
+            if let Some(clock) = clock {
+                limits.time = Some(clock::allocate(
+                    Duration::from_millis(clock),
+                    Duration::from_millis(increment),
+                    moves_to_go,
+                ));
+            }
+        }
+
+        limits
+    }
+
+    /// Handles `go`, searching on another thread so `stop` can still be read
+    /// * plays straight from the book while it has moves for the position
+    fn go(&mut self, args: &[&str]) -> io::Result<()> {
+        self.wait();
+
+        let book_move = match (&self.book, self.own_book) {
+            (Some(book), true) => book.choose(&self.board, &mut rand::thread_rng()),
+            _ => None,
+        };
+        if let Some(move_type) = book_move {
+            let best = move_type.to_uci(self.board.state.white, self.chess960);
+            return self.write(&format!("bestmove {}", best));
+        }
+
+        let mut search = Search::with_table(self.limits(args), self.table.clone());
+        search.endgames = self.endgames.clone();
+        self.stop = search.stop.clone();
+
+        let board = self.board.clone();
+        let white = self.board.state.white;
+        let chess960 = self.chess960;
+        let output = self.output.clone();
+        self.thinking = Some(thread::spawn(move || {
+            let result = search.run(&board, |result| {
+                let pv: Vec<String> = result
+                    .pv
+                    .iter()
+                    .enumerate()
+                    .map(|(i, move_type)| move_type.to_uci(white == (i % 2 == 0), chess960))
+                    .collect();
+                let score = match result.mate_in() {
+                    Some(moves) => format!("mate {}", moves),
+                    None => format!("cp {}", result.score),
+                };
+                let _ = send(
+                    &output,
+                    &format!(
+                        "info depth {} score {} nodes {} time {} pv {}",
+                        result.depth,
+                        score,
+                        result.nodes,
+                        result.time.as_millis(),
+                        pv.join(" ")
+                    ),
+                );
+            });
+
+            let best = match result.best_move {
+                Some(move_type) => move_type.to_uci(white, chess960),
+                None => String::from("0000"),
+            };
+            let _ = send(&output, &format!("bestmove {}", best));
+        }));
+        Ok(())
+    }
+}
+
+/// Writes a line and flushes it straight away so the GUI isn't kept waiting
+fn send(output: &Mutex<dyn Write + Send>, line: &str) -> io::Result<()> {
+    let mut output = match output.lock() {
+        Ok(output) => output,
+        Err(poisoned) => poisoned.into_inner(),
+    };
+    writeln!(output, "{}", line)?;
+    output.flush()
+}